@@ -1,12 +1,23 @@
+pub mod contacts;
 pub mod date_kind;
+pub mod diff;
 mod error;
 pub mod headings;
 pub mod link;
+pub mod link_kind;
+pub mod locale;
 pub mod meta_tag;
 mod page_info;
+pub mod pagination;
+pub mod query_params;
+pub mod region;
+pub mod rel;
+pub mod selector;
 pub mod text;
 pub mod url_facts;
+pub mod url_template;
 
+pub use diff::PageDiff;
 pub use headings::HeadingsVerbosity;
 pub use meta_tag::MetaVerbosity;
 pub use page_info::PageInfo;
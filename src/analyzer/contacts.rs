@@ -0,0 +1,462 @@
+use comfy_table::presets::UTF8_FULL_CONDENSED;
+use comfy_table::{Attribute, Cell, ContentArrangement, Table};
+use dom_content_extraction::scraper::{Html, Selector};
+use percent_encoding::percent_decode_str;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+use url::Url;
+
+use crate::analyzer::link::Link;
+use crate::analyzer::link_kind::LinkKind;
+use crate::output::RenderOutput;
+
+static SCRIPT_SELECTOR: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("script").unwrap());
+
+/// A social network recognized in profile links.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum SocialNetwork {
+    /// `x.com` and `twitter.com`.
+    X,
+    Facebook,
+    Linkedin,
+    Telegram,
+    Youtube,
+    Github,
+    Instagram,
+    Vk,
+}
+
+impl SocialNetwork {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::X => "x",
+            Self::Facebook => "facebook",
+            Self::Linkedin => "linkedin",
+            Self::Telegram => "telegram",
+            Self::Youtube => "youtube",
+            Self::Github => "github",
+            Self::Instagram => "instagram",
+            Self::Vk => "vk",
+        }
+    }
+
+    /// Network for a host, ignoring `www.`, `m.`, and `mobile.` prefixes.
+    fn from_host(host: &str) -> Option<Self> {
+        let host = host.to_ascii_lowercase();
+        let host = ["www.", "m.", "mobile."]
+            .iter()
+            .find_map(|prefix| host.strip_prefix(prefix))
+            .unwrap_or(&host);
+        let network = match host {
+            "x.com" | "twitter.com" => Self::X,
+            "facebook.com" | "fb.com" => Self::Facebook,
+            "linkedin.com" => Self::Linkedin,
+            "t.me" | "telegram.me" => Self::Telegram,
+            "youtube.com" => Self::Youtube,
+            "github.com" => Self::Github,
+            "instagram.com" => Self::Instagram,
+            "vk.com" => Self::Vk,
+            _ => return None,
+        };
+        Some(network)
+    }
+
+    /// First path segments that are site features rather than profiles.
+    fn reserved(self) -> &'static [&'static str] {
+        match self {
+            Self::X => &[
+                "intent", "share", "home", "search", "hashtag", "i", "explore",
+                "login",
+            ],
+            Self::Facebook => &[
+                "sharer",
+                "sharer.php",
+                "share",
+                "share.php",
+                "dialog",
+                "plugins",
+                "tr",
+                "login",
+            ],
+            Self::Linkedin => &[],
+            Self::Telegram => &["share", "joinchat", "addstickers", "iv"],
+            Self::Youtube => {
+                &["watch", "embed", "shorts", "playlist", "results", "feed"]
+            }
+            Self::Github => &[
+                "about",
+                "features",
+                "pricing",
+                "login",
+                "join",
+                "search",
+                "marketplace",
+                "sponsors",
+            ],
+            Self::Instagram => &["p", "reel", "reels", "explore", "accounts", "tv"],
+            Self::Vk => &["share.php", "away.php", "login", "feed"],
+        }
+    }
+}
+
+/// A social profile link reduced to the network's account identifier.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SocialProfile {
+    pub network: SocialNetwork,
+    /// Account name without `@`, lowercased. YouTube keeps its URL form
+    /// (`@name`, `channel/UC...`, `c/name`, `user/name`) since channel IDs are
+    /// case-sensitive; LinkedIn keeps `in/` or `company/`.
+    pub handle: String,
+    /// The link the handle came from, as found.
+    pub url: String,
+}
+
+/// Email addresses, phone numbers, and social profiles found on a page.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Contacts {
+    pub emails: Vec<String>,
+    pub phones: Vec<String>,
+    pub socials: Vec<SocialProfile>,
+}
+
+impl Contacts {
+    pub fn is_empty(&self) -> bool {
+        self.emails.is_empty() && self.phones.is_empty() && self.socials.is_empty()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ContactsOutput {
+    pub url: String,
+    pub contacts: Contacts,
+}
+
+/// Contacts from `mailto:`/`tel:` links, social profile links, and JSON-LD
+/// `sameAs` URLs, in that order without duplicates.
+pub fn extract_contacts(links: &[Link], same_as: &[String]) -> Contacts {
+    let mut contacts = Contacts::default();
+    for link in links {
+        match link.kind {
+            LinkKind::Mailto => {
+                for email in mailto_addresses(&link.url) {
+                    push_unique(&mut contacts.emails, email);
+                }
+            }
+            LinkKind::Tel => {
+                if let Some(phone) = tel_number(&link.url) {
+                    push_unique(&mut contacts.phones, phone);
+                }
+            }
+            _ => push_profile(&mut contacts.socials, &link.url),
+        }
+    }
+    for value in same_as {
+        if let Ok(url) = Url::parse(value) {
+            push_profile(&mut contacts.socials, &url);
+        }
+    }
+    contacts
+}
+
+/// Every `sameAs` URL in the page's JSON-LD blocks, wherever it is nested.
+/// Blocks that fail to parse are skipped.
+pub fn json_ld_same_as(document: &Html) -> Vec<String> {
+    let mut urls = Vec::new();
+    for script in document.select(&SCRIPT_SELECTOR) {
+        let is_json_ld = script.value().attr("type").is_some_and(|script_type| {
+            script_type.eq_ignore_ascii_case("application/ld+json")
+        });
+        if !is_json_ld {
+            continue;
+        }
+        if let Ok(value) = serde_json::from_str(&script.inner_html()) {
+            collect_same_as(&value, &mut urls);
+        }
+    }
+    urls
+}
+
+fn collect_same_as(value: &serde_json::Value, urls: &mut Vec<String>) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map {
+                if key == "sameAs" {
+                    let values = match value {
+                        serde_json::Value::Array(values) => values.as_slice(),
+                        value => std::slice::from_ref(value),
+                    };
+                    for url in values.iter().filter_map(|value| value.as_str()) {
+                        push_unique(urls, url.trim().to_string());
+                    }
+                } else {
+                    collect_same_as(value, urls);
+                }
+            }
+        }
+        serde_json::Value::Array(values) => {
+            for value in values {
+                collect_same_as(value, urls);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Profile for a social network URL; `None` for other hosts and for share,
+/// post, and feature pages.
+pub fn social_profile(url: &Url) -> Option<SocialProfile> {
+    let network = SocialNetwork::from_host(url.host_str()?)?;
+    let segments: Vec<&str> = url
+        .path_segments()?
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    let first = *segments.first()?;
+    if network
+        .reserved()
+        .iter()
+        .any(|reserved| first.eq_ignore_ascii_case(reserved))
+    {
+        return None;
+    }
+    let second = segments.get(1).copied();
+    let handle = match network {
+        SocialNetwork::Facebook if first == "profile.php" => url
+            .query_pairs()
+            .find(|(key, _)| key == "id")
+            .map(|(_, id)| id.into_owned())?,
+        SocialNetwork::Linkedin => match first {
+            "in" | "company" | "school" | "showcase" => {
+                format!("{first}/{}", second?.to_ascii_lowercase())
+            }
+            _ => return None,
+        },
+        SocialNetwork::Youtube => match first {
+            handle if handle.starts_with('@') => handle.to_ascii_lowercase(),
+            "channel" | "c" | "user" => format!("{first}/{}", second?),
+            _ => return None,
+        },
+        SocialNetwork::Github if first == "orgs" => second?.to_ascii_lowercase(),
+        _ => first.trim_start_matches('@').to_ascii_lowercase(),
+    };
+    if handle.is_empty() || handle.ends_with(".php") {
+        return None;
+    }
+    Some(SocialProfile {
+        network,
+        handle,
+        url: url.to_string(),
+    })
+}
+
+fn push_profile(socials: &mut Vec<SocialProfile>, url: &Url) {
+    let Some(profile) = social_profile(url) else {
+        return;
+    };
+    let seen = socials.iter().any(|existing| {
+        existing.network == profile.network && existing.handle == profile.handle
+    });
+    if !seen {
+        socials.push(profile);
+    }
+}
+
+/// Addresses of a `mailto:` URL: `mailto:a@x.com,b@x.com?subject=Hi` yields
+/// both addresses with the query dropped and the domain lowercased.
+fn mailto_addresses(url: &Url) -> Vec<String> {
+    percent_decode_str(url.path())
+        .decode_utf8_lossy()
+        .split(',')
+        .filter_map(|address| {
+            let (local, domain) = address.trim().rsplit_once('@')?;
+            (!local.is_empty() && domain.contains('.'))
+                .then(|| format!("{local}@{}", domain.to_ascii_lowercase()))
+        })
+        .collect()
+}
+
+/// Number of a `tel:` URL as `+` and digits only; extensions after `;` are
+/// dropped. `None` when fewer than three digits remain.
+fn tel_number(url: &Url) -> Option<String> {
+    let decoded = percent_decode_str(url.path()).decode_utf8_lossy();
+    let number = decoded.split(';').next().unwrap_or_default().trim();
+    let digits: String = number.chars().filter(char::is_ascii_digit).collect();
+    if digits.len() < 3 {
+        return None;
+    }
+    Some(if number.starts_with('+') {
+        format!("+{digits}")
+    } else {
+        digits
+    })
+}
+
+fn push_unique(values: &mut Vec<String>, value: String) {
+    if !values.contains(&value) {
+        values.push(value);
+    }
+}
+
+impl ContactsOutput {
+    fn render_value(&self) -> serde_json::Value {
+        serde_json::json!({
+            "url": self.url,
+            "emails": self.contacts.emails,
+            "phones": self.contacts.phones,
+            "socials": self.contacts.socials,
+        })
+    }
+}
+
+impl RenderOutput for ContactsOutput {
+    fn render_text(&self) -> String {
+        let contacts = &self.contacts;
+        if contacts.is_empty() {
+            return "No contacts found.\n".to_string();
+        }
+
+        let mut out = String::new();
+        out.push_str("## Contacts\n");
+        for (title, values) in
+            [("Emails", &contacts.emails), ("Phones", &contacts.phones)]
+        {
+            if values.is_empty() {
+                continue;
+            }
+            out.push_str(&format!("\n### {title}\n"));
+            for value in values {
+                out.push_str(&format!("- {value}\n"));
+            }
+        }
+
+        if !contacts.socials.is_empty() {
+            out.push_str("\n### Social Profiles\n");
+            let mut table = Table::new();
+            table.set_content_arrangement(ContentArrangement::Dynamic);
+            table.load_preset(UTF8_FULL_CONDENSED);
+            table.set_header(vec![
+                Cell::new("Network").add_attribute(Attribute::Bold),
+                Cell::new("Handle").add_attribute(Attribute::Bold),
+                Cell::new("URL").add_attribute(Attribute::Bold),
+            ]);
+            for profile in &contacts.socials {
+                table.add_row(vec![
+                    Cell::new(profile.network.as_str()),
+                    Cell::new(&profile.handle),
+                    Cell::new(&profile.url),
+                ]);
+            }
+            out.push_str(&table.to_string());
+            out.push('\n');
+        }
+        out
+    }
+
+    fn render_json(&self) -> String {
+        serde_json::to_string_pretty(&self.render_value()).unwrap_or_default()
+    }
+
+    fn render_toon(&self) -> String {
+        toon_format::encode_default(&self.render_value()).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::link;
+
+    fn profile(url: &str) -> Option<(SocialNetwork, String)> {
+        social_profile(&Url::parse(url).unwrap())
+            .map(|profile| (profile.network, profile.handle))
+    }
+
+    #[test]
+    fn normalizes_social_links_to_handles() {
+        assert_eq!(
+            profile("https://twitter.com/@Example?ref=nav"),
+            Some((SocialNetwork::X, "example".to_string()))
+        );
+        assert_eq!(
+            profile("https://www.linkedin.com/company/Acme/about/"),
+            Some((SocialNetwork::Linkedin, "company/acme".to_string()))
+        );
+        assert_eq!(
+            profile("https://www.youtube.com/channel/UCabcDEF"),
+            Some((SocialNetwork::Youtube, "channel/UCabcDEF".to_string()))
+        );
+        assert_eq!(
+            profile("https://m.facebook.com/profile.php?id=42"),
+            Some((SocialNetwork::Facebook, "42".to_string()))
+        );
+        assert_eq!(
+            profile("https://t.me/pginf_news"),
+            Some((SocialNetwork::Telegram, "pginf_news".to_string()))
+        );
+        assert_eq!(profile("https://twitter.com/intent/tweet?url=x"), None);
+        assert_eq!(profile("https://www.facebook.com/sharer/sharer.php"), None);
+        assert_eq!(profile("https://www.instagram.com/p/Cx1/"), None);
+        assert_eq!(profile("https://www.youtube.com/watch?v=1"), None);
+        assert_eq!(profile("https://github.com/"), None);
+        assert_eq!(profile("https://example.com/acme"), None);
+    }
+
+    #[test]
+    fn extracts_emails_phones_and_profiles_from_links() {
+        let html = r#"<a href="mailto:Info@Example.COM?subject=Hi">Mail</a>
+            <a href="mailto:a@example.com,info@example.com">Both</a>
+            <a href="tel:+1%20(555)%20010-0000">Call</a>
+            <a href="tel:+1-555-010-0000;ext=2">Call</a>
+            <a href="https://x.com/example">X</a>
+            <a href="https://twitter.com/Example">Twitter</a>
+            <a href="https://github.com/example">GitHub</a>"#;
+        let doc = Html::parse_document(html);
+        let links =
+            link::extract_links(&doc, &Url::parse("https://example.com/").unwrap());
+        let contacts = extract_contacts(&links, &[]);
+        assert_eq!(
+            contacts.emails,
+            ["Info@example.com", "a@example.com", "info@example.com"]
+        );
+        assert_eq!(contacts.phones, ["+15550100000"]);
+        let handles: Vec<_> = contacts
+            .socials
+            .iter()
+            .map(|profile| (profile.network, profile.handle.as_str()))
+            .collect();
+        assert_eq!(
+            handles,
+            [
+                (SocialNetwork::X, "example"),
+                (SocialNetwork::Github, "example")
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_same_as_from_json_ld() {
+        let html = r#"<script type="application/ld+json">
+            {"@graph": [{"@type": "Organization",
+              "sameAs": ["https://vk.com/acme", "https://en.wikipedia.org/wiki/Acme"]},
+             {"@type": "Person", "sameAs": "https://www.instagram.com/jane/"}]}
+            </script>
+            <script type="application/ld+json">{broken</script>"#;
+        let same_as = json_ld_same_as(&Html::parse_document(html));
+        assert_eq!(same_as.len(), 3);
+
+        let contacts = extract_contacts(&[], &same_as);
+        assert_eq!(contacts.socials.len(), 2);
+        assert_eq!(contacts.socials[0].network, SocialNetwork::Vk);
+        assert_eq!(contacts.socials[1].handle, "jane");
+
+        let output = ContactsOutput {
+            url: "https://example.com/".to_string(),
+            contacts,
+        };
+        assert!(output.render_text().contains("### Social Profiles"));
+        assert!(output.render_json().contains("\"network\": \"instagram\""));
+    }
+}
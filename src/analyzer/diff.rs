@@ -0,0 +1,399 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+use crate::analyzer::error::AnalyzerError;
+use crate::analyzer::page_info::PageInfo;
+use crate::cache::CachedPage;
+use crate::output::RenderOutput;
+
+/// Headers that change on every response and would drown real differences.
+const VOLATILE_HEADERS: &[&str] = &[
+    "age",
+    "cf-ray",
+    "date",
+    "etag",
+    "expires",
+    "last-modified",
+    "nel",
+    "report-to",
+    "server-timing",
+    "set-cookie",
+    "via",
+    "x-cache",
+    "x-request-id",
+    "x-served-by",
+    "x-timer",
+];
+
+/// Entries shown per section in text output; JSON and TOON list everything.
+const TEXT_SAMPLE_LIMIT: usize = 10;
+
+/// A single value that differs between two snapshots.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ValueChange {
+    pub field: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+/// Members that appeared in or vanished from a set-like field.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SetChange {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl SetChange {
+    fn between(old: BTreeSet<String>, new: BTreeSet<String>) -> Self {
+        Self {
+            added: new.difference(&old).cloned().collect(),
+            removed: old.difference(&new).cloned().collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct TextChange {
+    pub old_length: usize,
+    pub new_length: usize,
+    pub lines: SetChange,
+}
+
+/// Differences between two cached snapshots of the same page.
+#[derive(Debug, Clone, Serialize)]
+pub struct PageDiff {
+    pub url: String,
+    pub old_fetched_at: String,
+    pub new_fetched_at: String,
+    /// Status, final URL, title, and language changes.
+    pub fields: Vec<ValueChange>,
+    pub headers: Vec<ValueChange>,
+    pub meta: Vec<ValueChange>,
+    pub links: SetChange,
+    pub headings: SetChange,
+    pub text: TextChange,
+}
+
+impl PageDiff {
+    /// Compares `old` against `new`; additions are what `new` has and `old`
+    /// lacks.
+    pub fn between(
+        old: &CachedPage,
+        new: &CachedPage,
+    ) -> Result<Self, AnalyzerError> {
+        let old_page = PageInfo::from_cached_page(old)?;
+        let new_page = PageInfo::from_cached_page(new)?;
+
+        let mut fields = Vec::new();
+        push_change(
+            &mut fields,
+            "status",
+            Some(old_page.status.to_string()),
+            Some(new_page.status.to_string()),
+        );
+        push_change(
+            &mut fields,
+            "final_url",
+            Some(old_page.final_url.clone()),
+            Some(new_page.final_url.clone()),
+        );
+        push_change(
+            &mut fields,
+            "title",
+            old_page.title.clone(),
+            new_page.title.clone(),
+        );
+        push_change(
+            &mut fields,
+            "lang",
+            old_page.lang.clone(),
+            new_page.lang.clone(),
+        );
+
+        let text_lines = |page: &PageInfo| -> BTreeSet<String> {
+            page.text_content
+                .as_deref()
+                .unwrap_or_default()
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(String::from)
+                .collect()
+        };
+
+        Ok(Self {
+            url: new_page.url.clone(),
+            old_fetched_at: old.fetch.fetched_at.clone(),
+            new_fetched_at: new.fetch.fetched_at.clone(),
+            fields,
+            headers: map_changes(header_map(old), header_map(new)),
+            meta: map_changes(meta_map(&old_page), meta_map(&new_page)),
+            links: SetChange::between(link_set(&old_page), link_set(&new_page)),
+            headings: SetChange::between(
+                heading_set(&old_page),
+                heading_set(&new_page),
+            ),
+            text: TextChange {
+                old_length: old_page.text_content.as_deref().map_or(0, str::len),
+                new_length: new_page.text_content.as_deref().map_or(0, str::len),
+                lines: SetChange::between(
+                    text_lines(&old_page),
+                    text_lines(&new_page),
+                ),
+            },
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+            && self.headers.is_empty()
+            && self.meta.is_empty()
+            && self.links.is_empty()
+            && self.headings.is_empty()
+            && self.text.lines.is_empty()
+    }
+
+    fn render_value(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or_default()
+    }
+}
+
+fn push_change(
+    changes: &mut Vec<ValueChange>,
+    field: &str,
+    old: Option<String>,
+    new: Option<String>,
+) {
+    if old != new {
+        changes.push(ValueChange {
+            field: field.to_string(),
+            old,
+            new,
+        });
+    }
+}
+
+fn map_changes(
+    old: BTreeMap<String, String>,
+    new: BTreeMap<String, String>,
+) -> Vec<ValueChange> {
+    let names: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    let mut changes = Vec::new();
+    for name in names {
+        push_change(
+            &mut changes,
+            name,
+            old.get(name).cloned(),
+            new.get(name).cloned(),
+        );
+    }
+    changes
+}
+
+fn header_map(page: &CachedPage) -> BTreeMap<String, String> {
+    page.headers
+        .iter()
+        .map(|(name, value)| (name.to_ascii_lowercase(), value.clone()))
+        .filter(|(name, _)| !VOLATILE_HEADERS.contains(&name.as_str()))
+        .collect()
+}
+
+/// Meta tags keyed by name; repeated names (e.g. `og:image`) are joined.
+fn meta_map(page: &PageInfo) -> BTreeMap<String, String> {
+    let mut map: BTreeMap<String, String> = BTreeMap::new();
+    for tag in &page.meta {
+        let (Some(name), Some(content)) = (&tag.name, &tag.content) else {
+            continue;
+        };
+        map.entry(name.to_ascii_lowercase())
+            .and_modify(|joined| {
+                joined.push_str(" | ");
+                joined.push_str(content);
+            })
+            .or_insert_with(|| content.clone());
+    }
+    map
+}
+
+fn link_set(page: &PageInfo) -> BTreeSet<String> {
+    page.links
+        .iter()
+        .filter(|link| link.element.is_navigational())
+        .map(|link| link.url.to_string())
+        .collect()
+}
+
+fn heading_set(page: &PageInfo) -> BTreeSet<String> {
+    page.headings
+        .as_pairs()
+        .into_iter()
+        .flat_map(|(tag, texts)| {
+            texts.iter().map(move |text| format!("{tag}: {text}"))
+        })
+        .collect()
+}
+
+fn render_value_changes(out: &mut String, title: &str, changes: &[ValueChange]) {
+    if changes.is_empty() {
+        return;
+    }
+    out.push_str(&format!("\n### {title}\n"));
+    for change in changes {
+        out.push_str(&format!(
+            "- {}: {} -> {}\n",
+            change.field,
+            change.old.as_deref().unwrap_or("(none)"),
+            change.new.as_deref().unwrap_or("(none)"),
+        ));
+    }
+}
+
+fn render_set_change(out: &mut String, title: &str, change: &SetChange) {
+    if change.is_empty() {
+        return;
+    }
+    out.push_str(&format!(
+        "\n### {title} (+{} / -{})\n",
+        change.added.len(),
+        change.removed.len()
+    ));
+    for (sign, items) in [("+", &change.added), ("-", &change.removed)] {
+        for item in items.iter().take(TEXT_SAMPLE_LIMIT) {
+            out.push_str(&format!("{sign} {item}\n"));
+        }
+        if items.len() > TEXT_SAMPLE_LIMIT {
+            out.push_str(&format!(
+                "{sign} ... and {} more\n",
+                items.len() - TEXT_SAMPLE_LIMIT
+            ));
+        }
+    }
+}
+
+impl RenderOutput for PageDiff {
+    fn render_text(&self) -> String {
+        let mut out = String::new();
+        out.push_str("## Page Diff\n");
+        out.push_str(&format!("URL: {}\n", self.url));
+        out.push_str(&format!(
+            "Snapshots: {} -> {}\n",
+            self.old_fetched_at, self.new_fetched_at
+        ));
+
+        if self.is_empty() {
+            out.push_str("\nNo changes.\n");
+            return out;
+        }
+
+        render_value_changes(&mut out, "Fields", &self.fields);
+        render_value_changes(&mut out, "Headers", &self.headers);
+        render_value_changes(&mut out, "Meta", &self.meta);
+        render_set_change(&mut out, "Links", &self.links);
+        render_set_change(&mut out, "Headings", &self.headings);
+        if !self.text.lines.is_empty() {
+            out.push_str(&format!(
+                "\nText length: {} -> {}\n",
+                self.text.old_length, self.text.new_length
+            ));
+            render_set_change(&mut out, "Text lines", &self.text.lines);
+        }
+        out
+    }
+
+    fn render_json(&self) -> String {
+        serde_json::to_string_pretty(&self.render_value()).unwrap_or_default()
+    }
+
+    fn render_toon(&self) -> String {
+        toon_format::encode_default(&self.render_value()).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::cache::CachedFetch;
+
+    fn snapshot(
+        fetched_at: &str,
+        status: u16,
+        headers: &[(&str, &str)],
+        html: &str,
+    ) -> CachedPage {
+        CachedPage {
+            fetch: CachedFetch {
+                input_url: "https://example.com/".to_string(),
+                final_url: "https://example.com/".to_string(),
+                status,
+                fetched_at: fetched_at.to_string(),
+                ..Default::default()
+            },
+            headers: headers
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<HashMap<_, _>>(),
+            html: html.to_string(),
+        }
+    }
+
+    #[test]
+    fn identical_snapshots_have_no_changes() {
+        let html = "<html><head><title>Home</title></head><body><h1>News</h1></body></html>";
+        let old = snapshot("10", 200, &[("date", "Mon")], html);
+        let new = snapshot("20", 200, &[("date", "Tue")], html);
+
+        let diff = PageDiff::between(&old, &new).unwrap();
+        assert!(diff.is_empty(), "{diff:?}");
+        assert!(diff.render_text().contains("No changes."));
+    }
+
+    #[test]
+    fn reports_field_header_meta_link_and_heading_changes() {
+        let old = snapshot(
+            "10",
+            200,
+            &[("content-type", "text/html"), ("server", "nginx")],
+            r#"<html><head><title>Home</title>
+               <meta name="description" content="old"></head>
+               <body><h1>News</h1><a href="/a">A</a><a href="/b">B</a></body></html>"#,
+        );
+        let new = snapshot(
+            "20",
+            404,
+            &[("content-type", "text/html"), ("server", "envoy")],
+            r#"<html><head><title>Home v2</title>
+               <meta name="description" content="new"></head>
+               <body><h1>Latest</h1><a href="/a">A</a><a href="/c">C</a></body></html>"#,
+        );
+
+        let diff = PageDiff::between(&old, &new).unwrap();
+        let fields: Vec<&str> =
+            diff.fields.iter().map(|c| c.field.as_str()).collect();
+        assert_eq!(fields, vec!["status", "title"]);
+        assert_eq!(
+            diff.headers,
+            vec![ValueChange {
+                field: "server".to_string(),
+                old: Some("nginx".to_string()),
+                new: Some("envoy".to_string()),
+            }]
+        );
+        assert_eq!(diff.meta[0].field, "description");
+        assert_eq!(diff.links.added, vec!["https://example.com/c"]);
+        assert_eq!(diff.links.removed, vec!["https://example.com/b"]);
+        assert_eq!(diff.headings.added, vec!["h1: Latest"]);
+        assert_eq!(diff.headings.removed, vec!["h1: News"]);
+
+        let text = diff.render_text();
+        assert!(text.contains("- status: 200 -> 404"));
+        assert!(text.contains("### Links (+1 / -1)"));
+        assert!(text.contains("+ https://example.com/c"));
+    }
+}
@@ -42,7 +42,7 @@ impl Headings {
             && self.h6.is_empty()
     }
 
-    fn as_pairs(&self) -> [(&str, &[String]); 6] {
+    pub(crate) fn as_pairs(&self) -> [(&str, &[String]); 6] {
         [
             ("h1", &self.h1),
             ("h2", &self.h2),
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::LazyLock;
 
 use comfy_table::presets::UTF8_FULL_CONDENSED;
@@ -6,16 +7,134 @@ use dom_content_extraction::scraper::{Html, Selector};
 use serde::{Deserialize, Serialize};
 use url::Url;
 
+use crate::analyzer::link_kind::{self, LinkKind};
+use crate::analyzer::locale::HreflangAlternate;
+use crate::analyzer::pagination::{FollowedPage, Pagination};
+use crate::analyzer::query_params::QueryParam;
+use crate::analyzer::region::{self, LinkRegion};
+use crate::analyzer::rel::{self, RelToken};
+use crate::analyzer::selector;
+use crate::analyzer::url_facts::{AnchorStats, HostCount};
+use crate::analyzer::url_template::UrlTemplate;
+use crate::normalize::NormalizePolicy;
 use crate::output::RenderOutput;
 
-static A_HREF: LazyLock<Selector> =
-    LazyLock::new(|| Selector::parse("a[href]").unwrap());
+static LINK_BEARING: LazyLock<Selector> = LazyLock::new(|| {
+    Selector::parse(
+        "a[href], link[href], area[href], iframe[src], img[src], img[srcset], \
+         source[src], source[srcset], form[action], script[src], \
+         [data-href], [data-url]",
+    )
+    .unwrap()
+});
+
+const MAX_SELECTOR_GROUPS: usize = 20;
+const MAX_SELECTOR_SAMPLES: usize = 3;
+
+static IMAGE_CONTENT: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("img, svg, picture").unwrap());
+
+static BASE_HREF: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("base[href]").unwrap());
+
+/// Element kind a link was found on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkElement {
+    A,
+    Link,
+    Area,
+    Iframe,
+    Img,
+    Source,
+    Form,
+    Script,
+    /// `data-href` / `data-url` on any element.
+    Data,
+}
+
+impl LinkElement {
+    pub const ALL: [LinkElement; 9] = [
+        LinkElement::A,
+        LinkElement::Link,
+        LinkElement::Area,
+        LinkElement::Iframe,
+        LinkElement::Img,
+        LinkElement::Source,
+        LinkElement::Form,
+        LinkElement::Script,
+        LinkElement::Data,
+    ];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.as_str() == value)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::A => "a",
+            Self::Link => "link",
+            Self::Area => "area",
+            Self::Iframe => "iframe",
+            Self::Img => "img",
+            Self::Source => "source",
+            Self::Form => "form",
+            Self::Script => "script",
+            Self::Data => "data",
+        }
+    }
+
+    /// `<a>` and `<area>`: links a user can follow to another page.
+    pub fn is_navigational(self) -> bool {
+        matches!(self, Self::A | Self::Area)
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RawLink {
     pub href: String,
     pub text: Option<String>,
     pub rel: Option<String>,
+    /// Known tokens parsed from `rel`.
+    pub rel_tokens: Vec<RelToken>,
+    pub target: Option<String>,
+    pub hreflang: Option<String>,
+    /// The `type` attribute: advertised MIME type of the target.
+    pub media_type: Option<String>,
+    /// The `download` attribute; empty when present without a file name.
+    pub download: Option<String>,
+    pub title: Option<String>,
+    pub element: LinkElement,
+    /// Attribute the URL came from: `href`, `src`, `srcset`, `action`, ...
+    pub attribute: String,
+    pub region: LinkRegion,
+    /// CSS selector path to the element, e.g. `main > ul > li:nth-of-type(2) > a`.
+    pub selector: String,
+    /// `selector` without positions; shared by repeating siblings.
+    pub generalized_selector: String,
+    /// An `<a>` with no text whose content is an image or icon.
+    pub image_only: bool,
+}
+
+/// Where a link points relative to the page host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkScope {
+    /// Exactly the page host.
+    SameHost,
+    /// Another host under the page's registered domain (`blog.example.com`).
+    Subdomain,
+    External,
+}
+
+impl LinkScope {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::SameHost => "same-host",
+            Self::Subdomain => "subdomain",
+            Self::External => "external",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,14 +143,40 @@ pub struct Link {
     pub url: Url,
     pub text: Option<String>,
     pub rel: Option<String>,
+    /// Known tokens parsed from `rel`.
+    pub rel_tokens: Vec<RelToken>,
+    pub target: Option<String>,
+    pub hreflang: Option<String>,
+    /// The `type` attribute: advertised MIME type of the target.
+    pub media_type: Option<String>,
+    /// The `download` attribute; empty when present without a file name.
+    pub download: Option<String>,
+    pub title: Option<String>,
+    /// Same registered domain as the page: `scope` is not `External`.
     pub is_internal: bool,
+    pub scope: LinkScope,
+    pub kind: LinkKind,
+    pub element: LinkElement,
+    pub attribute: String,
+    pub region: LinkRegion,
+    /// CSS selector path to the element, e.g. `main > ul > li:nth-of-type(2) > a`.
+    pub selector: String,
+    /// `selector` without positions; shared by repeating siblings.
+    pub generalized_selector: String,
+    /// An `<a>` with no text whose content is an image or icon.
+    pub image_only: bool,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum LinkFilter {
+    #[default]
     All,
     Internal,
     External,
+    SameHost,
+    Subdomain,
+    /// Links of one kind: `image`, `stylesheet`, `mailto`, ...
+    Kind(LinkKind),
 }
 
 impl LinkFilter {
@@ -40,7 +185,9 @@ impl LinkFilter {
             "all" => Some(Self::All),
             "internal" => Some(Self::Internal),
             "external" => Some(Self::External),
-            _ => None,
+            "same-host" => Some(Self::SameHost),
+            "subdomain" => Some(Self::Subdomain),
+            _ => LinkKind::parse(value).map(Self::Kind),
         }
     }
 
@@ -49,6 +196,9 @@ impl LinkFilter {
             Self::All => "all",
             Self::Internal => "internal",
             Self::External => "external",
+            Self::SameHost => "same-host",
+            Self::Subdomain => "subdomain",
+            Self::Kind(kind) => kind.as_str(),
         }
     }
 }
@@ -58,59 +208,227 @@ pub struct LinkGroup {
     pub section: String,
     pub count: usize,
     pub samples: Vec<String>,
+    pub anchors: AnchorStats,
+}
+
+/// Links sharing a generalized selector: a repeating listing or menu.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelectorGroup {
+    pub selector: String,
+    pub count: usize,
+    pub samples: Vec<String>,
+}
+
+/// One distinct URL among a list of links.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkOccurrence {
+    pub url: String,
+    pub scope: LinkScope,
+    pub count: usize,
+    /// Distinct non-empty anchor texts in document order.
+    pub texts: Vec<String>,
+    /// Occurrences that are image-only anchors.
+    pub image_links: usize,
+    /// 1-based position of the first occurrence.
+    pub first_position: usize,
+}
+
+/// Which links a `LinksOutput` shows.
+#[derive(Debug, Clone, Default)]
+pub struct LinksQuery {
+    pub filter: LinkFilter,
+    /// Element kinds to keep; empty keeps every kind.
+    pub elements: Vec<LinkElement>,
+    /// Page regions to keep; empty keeps every region. `main` also keeps
+    /// `article` links (see `LinkRegion::includes`).
+    pub regions: Vec<LinkRegion>,
+    /// Show one row per distinct URL instead of every link.
+    pub dedupe: bool,
+}
+
+impl LinksQuery {
+    pub fn matches(&self, link: &Link) -> bool {
+        let by_target = match self.filter {
+            LinkFilter::All => true,
+            LinkFilter::Internal => link.is_internal,
+            LinkFilter::External => !link.is_internal,
+            LinkFilter::SameHost => link.scope == LinkScope::SameHost,
+            LinkFilter::Subdomain => link.scope == LinkScope::Subdomain,
+            LinkFilter::Kind(kind) => link.kind == kind,
+        };
+        by_target
+            && (self.elements.is_empty() || self.elements.contains(&link.element))
+            && (self.regions.is_empty()
+                || self
+                    .regions
+                    .iter()
+                    .any(|region| region.includes(link.region)))
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct LinksOutput {
     pub url: String,
+    /// Document base from `<base href>`, when the page declares one.
+    pub base: Option<String>,
     pub filter: LinkFilter,
+    pub elements: Vec<LinkElement>,
+    pub regions: Vec<LinkRegion>,
     pub total_internal: usize,
     pub total_external: usize,
+    pub total_same_host: usize,
+    pub total_subdomain: usize,
+    pub host_counts: Vec<HostCount>,
+    pub top_external_domains: Vec<(String, usize)>,
+    /// Navigational links per page region.
+    pub region_counts: Vec<(LinkRegion, usize)>,
+    /// Locales found in internal URLs, most linked first.
+    pub locales: Vec<(String, usize)>,
+    pub alternates: Vec<HreflangAlternate>,
+    /// Shown links per `rel` token.
+    pub rel_counts: Vec<(RelToken, usize)>,
+    /// Shown links carrying `target=_blank`, `hreflang`, `type`, `download`,
+    /// or `title`.
+    pub attribute_counts: Vec<(&'static str, usize)>,
     pub links: Vec<Link>,
+    /// Set with `LinksQuery::dedupe`: `links` collapsed by URL.
+    pub occurrences: Option<Vec<LinkOccurrence>>,
     pub groups: Vec<LinkGroup>,
+    pub selector_groups: Vec<SelectorGroup>,
+    pub url_templates: Vec<UrlTemplate>,
+    pub pagination: Option<Pagination>,
+    /// Pages merged in by `--follow-next`, starting with this one.
+    pub followed: Vec<FollowedPage>,
+    pub query_params: Vec<QueryParam>,
     pub depth_distribution: Vec<(usize, usize)>,
     pub utility_urls: Vec<String>,
 }
 
-/// Extract raw `<a href>` evidence from an HTML document in document order.
+/// Extract raw link evidence from every link-bearing element and attribute
+/// in document order. `srcset` yields one link per candidate.
 pub fn extract_raw_links(document: &Html) -> Vec<RawLink> {
-    document
-        .select(&A_HREF)
-        .filter_map(|element| {
-            let href = element.value().attr("href")?;
-            let text = element
-                .text()
-                .collect::<Vec<_>>()
-                .join("")
-                .trim()
-                .to_string();
-            let text = if text.is_empty() { None } else { Some(text) };
-
-            Some(RawLink {
+    let mut links = Vec::new();
+    for element in document.select(&LINK_BEARING) {
+        let value = element.value();
+        let kind = match value.name() {
+            "a" => Some((LinkElement::A, "href")),
+            "link" => Some((LinkElement::Link, "href")),
+            "area" => Some((LinkElement::Area, "href")),
+            "iframe" => Some((LinkElement::Iframe, "src")),
+            "img" => Some((LinkElement::Img, "src")),
+            "source" => Some((LinkElement::Source, "src")),
+            "form" => Some((LinkElement::Form, "action")),
+            "script" => Some((LinkElement::Script, "src")),
+            _ => None,
+        };
+
+        let text = match kind.map(|(kind, _)| kind) {
+            Some(LinkElement::A) => Some(
+                element
+                    .text()
+                    .collect::<Vec<_>>()
+                    .join("")
+                    .trim()
+                    .to_string(),
+            ),
+            Some(LinkElement::Area | LinkElement::Img) => {
+                value.attr("alt").map(|alt| alt.trim().to_string())
+            }
+            _ => None,
+        }
+        .filter(|text| !text.is_empty());
+        let image_only = kind.is_some_and(|(kind, _)| kind == LinkElement::A)
+            && text.is_none()
+            && element.select(&IMAGE_CONTENT).next().is_some();
+        let rel = value.attr("rel").map(String::from);
+        let rel_tokens = rel.as_deref().map(rel::parse_rel).unwrap_or_default();
+        let attr = |name: &str| value.attr(name).map(|v| v.trim().to_string());
+        let target = attr("target").filter(|target| !target.is_empty());
+        let hreflang = attr("hreflang").filter(|lang| !lang.is_empty());
+        let media_type = attr("type").filter(|media_type| !media_type.is_empty());
+        let download = attr("download");
+        let title = attr("title").filter(|title| !title.is_empty());
+        let region = region::link_region(element);
+        let path = selector::selector_path(element);
+
+        let mut push = |element: LinkElement, attribute: &str, href: &str| {
+            links.push(RawLink {
                 href: href.to_string(),
-                text,
-                rel: element.value().attr("rel").map(String::from),
-            })
-        })
-        .collect()
+                text: text.clone(),
+                rel: rel.clone(),
+                rel_tokens: rel_tokens.clone(),
+                target: target.clone(),
+                hreflang: hreflang.clone(),
+                media_type: media_type.clone(),
+                download: download.clone(),
+                title: title.clone(),
+                element,
+                attribute: attribute.to_string(),
+                region,
+                selector: path.exact.clone(),
+                generalized_selector: path.generalized.clone(),
+                image_only,
+            });
+        };
+
+        if let Some((kind, attribute)) = kind {
+            if let Some(href) = value.attr(attribute) {
+                push(kind, attribute, href);
+            }
+            if matches!(kind, LinkElement::Img | LinkElement::Source)
+                && let Some(srcset) = value.attr("srcset")
+            {
+                for candidate in srcset_urls(srcset) {
+                    push(kind, "srcset", candidate);
+                }
+            }
+        }
+        for attribute in ["data-href", "data-url"] {
+            if let Some(href) = value.attr(attribute) {
+                push(LinkElement::Data, attribute, href);
+            }
+        }
+    }
+    links
+}
+
+/// URLs of the candidates in a `srcset` (`a.png 1x, b.png 2x`).
+fn srcset_urls(srcset: &str) -> impl Iterator<Item = &str> {
+    srcset
+        .split(',')
+        .filter_map(|candidate| candidate.split_whitespace().next())
+}
+
+/// The first `<base href>` resolved against the page URL. `None` when the
+/// page has no base element or its href is not an http(s) URL.
+pub fn document_base(document: &Html, page_url: &Url) -> Option<Url> {
+    let href = document.select(&BASE_HREF).next()?.value().attr("href")?;
+    let base = page_url.join(href.trim()).ok()?;
+    matches!(base.scheme(), "http" | "https").then_some(base)
 }
 
 /// Extract all links from an HTML document, resolving relative URLs against
-/// the page's base URL. Returned links are normalized (lowercase host, no fragment).
-pub fn extract_links(document: &Html, base_url: &Url) -> Vec<Link> {
+/// the document base (`<base href>` or the page URL). Returned links are
+/// normalized (lowercase host, no fragment).
+#[allow(dead_code)]
+pub fn extract_links(document: &Html, page_url: &Url) -> Vec<Link> {
     let opts = LinkOptions {
         normalize: true,
         ..Default::default()
     };
-    extract_links_inner(document, base_url, &opts)
+    extract_links_with(document, page_url, &opts)
 }
 
-fn extract_links_inner(
+/// Like [`extract_links`], with explicit normalization, tracking, and limit
+/// options.
+pub fn extract_links_with(
     document: &Html,
-    base_url: &Url,
+    page_url: &Url,
     opts: &LinkOptions,
 ) -> Vec<Link> {
-    let page_domain = extract_registered_domain(base_url);
+    let page_domain = extract_registered_domain(page_url);
+    let base_url = document_base(document, page_url);
+    let base_url = base_url.as_ref().unwrap_or(page_url);
 
     let mut links: Vec<Link> = extract_raw_links(document)
         .into_iter()
@@ -121,23 +439,51 @@ fn extract_links_inner(
                 (Some(a), Some(b)) => a == b,
                 _ => false,
             };
+            let scope = if !is_internal {
+                LinkScope::External
+            } else if resolved.host_str() == page_url.host_str() {
+                LinkScope::SameHost
+            } else {
+                LinkScope::Subdomain
+            };
+
+            let kind = link_kind::link_kind(
+                &resolved,
+                raw.element,
+                raw.rel.as_deref(),
+                raw.media_type.as_deref(),
+            );
 
             Some(Link {
                 raw_url: raw.href,
                 url: resolved,
                 text: raw.text,
                 rel: raw.rel,
+                rel_tokens: raw.rel_tokens,
+                target: raw.target,
+                hreflang: raw.hreflang,
+                media_type: raw.media_type,
+                download: raw.download,
+                title: raw.title,
                 is_internal,
+                scope,
+                kind,
+                element: raw.element,
+                attribute: raw.attribute,
+                region: raw.region,
+                selector: raw.selector,
+                generalized_selector: raw.generalized_selector,
+                image_only: raw.image_only,
             })
         })
         .collect();
 
     for link in &mut links {
         if opts.normalize {
-            link.normalize();
+            link.normalize_with(&opts.url_policy);
         }
         if opts.strip_tracking_params {
-            link.strip_tracking();
+            link.strip_tracking_with(&opts.url_policy);
         }
     }
 
@@ -148,6 +494,100 @@ fn extract_links_inner(
     links
 }
 
+/// Collapses links by URL in order of first occurrence.
+pub fn dedupe_links(links: &[Link]) -> Vec<LinkOccurrence> {
+    let mut occurrences: Vec<LinkOccurrence> = Vec::new();
+    let mut index_by_url: HashMap<&str, usize> = HashMap::new();
+    for (position, link) in links.iter().enumerate() {
+        let index = *index_by_url.entry(link.url.as_str()).or_insert_with(|| {
+            occurrences.push(LinkOccurrence {
+                url: link.url.to_string(),
+                scope: link.scope,
+                count: 0,
+                texts: Vec::new(),
+                image_links: 0,
+                first_position: position + 1,
+            });
+            occurrences.len() - 1
+        });
+        let occurrence = &mut occurrences[index];
+        occurrence.count += 1;
+        if link.image_only {
+            occurrence.image_links += 1;
+        }
+        if let Some(text) = &link.text
+            && !occurrence.texts.contains(text)
+        {
+            occurrence.texts.push(text.clone());
+        }
+    }
+    occurrences
+}
+
+/// Groups links by generalized selector, largest groups first.
+pub fn selector_groups(links: &[Link]) -> Vec<SelectorGroup> {
+    let mut groups: Vec<SelectorGroup> = Vec::new();
+    for link in links {
+        let url = link.url.to_string();
+        match groups
+            .iter_mut()
+            .find(|group| group.selector == link.generalized_selector)
+        {
+            Some(group) => {
+                group.count += 1;
+                if group.samples.len() < MAX_SELECTOR_SAMPLES
+                    && !group.samples.contains(&url)
+                {
+                    group.samples.push(url);
+                }
+            }
+            None => groups.push(SelectorGroup {
+                selector: link.generalized_selector.clone(),
+                count: 1,
+                samples: vec![url],
+            }),
+        }
+    }
+    groups.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| a.selector.cmp(&b.selector))
+    });
+    groups.truncate(MAX_SELECTOR_GROUPS);
+    groups
+}
+
+/// Links per `rel` token, in token order; tokens no link carries are left out.
+pub fn rel_counts(links: &[Link]) -> Vec<(RelToken, usize)> {
+    RelToken::ALL
+        .into_iter()
+        .map(|token| (token, links.iter().filter(|l| l.has_rel(token)).count()))
+        .filter(|(_, count)| *count > 0)
+        .collect()
+}
+
+/// Links carrying crawler-relevant attributes; zero counts are left out.
+pub fn attribute_counts(links: &[Link]) -> Vec<(&'static str, usize)> {
+    let count = |has: fn(&Link) -> bool| links.iter().filter(|l| has(l)).count();
+    [
+        (
+            "target=_blank",
+            count(|l| {
+                l.target
+                    .as_deref()
+                    .is_some_and(|t| t.eq_ignore_ascii_case("_blank"))
+            }),
+        ),
+        ("hreflang", count(|l| l.hreflang.is_some())),
+        ("type", count(|l| l.media_type.is_some())),
+        ("download", count(|l| l.download.is_some())),
+        ("title", count(|l| l.title.is_some())),
+    ]
+    .into_iter()
+    .filter(|(_, count)| *count > 0)
+    .collect()
+}
+
 /// Extract the registered domain using the Public Suffix List
 /// (e.g. "example.com" from "https://www.example.com/page").
 pub fn extract_registered_domain(url: &Url) -> Option<String> {
@@ -155,37 +595,28 @@ pub fn extract_registered_domain(url: &Url) -> Option<String> {
 }
 
 impl Link {
+    #[allow(dead_code)]
     pub fn normalize(&mut self) {
-        if let Some(host) = self.url.host_str().map(|h| h.to_ascii_lowercase()) {
-            let _ = self.url.set_host(Some(&host));
-        }
-        self.url.set_fragment(None);
+        self.normalize_with(&NormalizePolicy::default());
     }
 
-    pub fn strip_tracking(&mut self) {
-        const TRACKING: &[&str] = &[
-            "utm_source",
-            "utm_medium",
-            "utm_campaign",
-            "utm_term",
-            "utm_content",
-            "utm_id",
-            "fbclid",
-            "gclid",
-        ];
+    /// Baseline normalization plus the rules enabled in `policy`.
+    pub fn normalize_with(&mut self, policy: &NormalizePolicy) {
+        policy.apply(&mut self.url);
+    }
 
-        let pairs: Vec<(String, String)> = self
-            .url
-            .query_pairs()
-            .filter(|(k, _)| !TRACKING.iter().any(|tk| k.eq_ignore_ascii_case(tk)))
-            .map(|(k, v)| (k.into_owned(), v.into_owned()))
-            .collect();
+    pub fn has_rel(&self, token: RelToken) -> bool {
+        self.rel_tokens.contains(&token)
+    }
 
-        if pairs.is_empty() {
-            self.url.set_query(None);
-        } else {
-            self.url.query_pairs_mut().clear().extend_pairs(pairs);
-        }
+    #[allow(dead_code)]
+    pub fn strip_tracking(&mut self) {
+        self.strip_tracking_with(&NormalizePolicy::default());
+    }
+
+    /// Drops the tracking parameters listed in `policy` from the query.
+    pub fn strip_tracking_with(&mut self, policy: &NormalizePolicy) {
+        policy.strip_tracking_params(&mut self.url);
     }
 
     #[allow(dead_code)]
@@ -193,18 +624,9 @@ impl Link {
         self.url.host_str() == other.host_str()
     }
 
-    #[allow(dead_code)]
+    /// Images, stylesheets, scripts, fonts, media, documents, and archives.
     pub fn is_asset(&self) -> bool {
-        const EXTS: &[&str] = &[
-            "css", "js", "mjs", "png", "jpg", "jpeg", "gif", "svg", "webp", "ico",
-            "bmp", "woff", "woff2", "ttf", "eot", "otf", "mp4", "webm", "mp3",
-            "ogg", "wav", "pdf", "doc", "docx", "xls", "xlsx", "zip", "tar", "gz",
-        ];
-
-        if let Some(ext) = self.url.path().rsplit('.').next() {
-            return EXTS.iter().any(|e| e.eq_ignore_ascii_case(ext));
-        }
-        false
+        self.kind.is_asset()
     }
 }
 
@@ -224,21 +646,93 @@ impl LinksOutput {
                     "url": link.url.as_str(),
                     "text": link.text,
                     "rel": link.rel,
+                    "rel_tokens": link
+                        .rel_tokens
+                        .iter()
+                        .map(|token| token.as_str())
+                        .collect::<Vec<_>>(),
+                    "target": link.target,
+                    "hreflang": link.hreflang,
+                    "type": link.media_type,
+                    "download": link.download,
+                    "title": link.title,
                     "is_internal": link.is_internal,
+                    "scope": link.scope.as_str(),
+                    "kind": link.kind.as_str(),
+                    "element": link.element.as_str(),
+                    "attribute": link.attribute,
+                    "region": link.region.as_str(),
+                    "selector": link.selector,
+                    "generalized_selector": link.generalized_selector,
+                    "image_only": link.image_only,
                 })
             })
             .collect();
         let mut obj = serde_json::json!({
             "url": &self.url,
+            "base": &self.base,
             "filter": self.filter.as_str(),
+            "elements": self
+                .elements
+                .iter()
+                .map(|element| element.as_str())
+                .collect::<Vec<_>>(),
+            "regions": self
+                .regions
+                .iter()
+                .map(|region| region.as_str())
+                .collect::<Vec<_>>(),
             "total_internal": self.total_internal,
             "total_external": self.total_external,
+            "total_same_host": self.total_same_host,
+            "total_subdomain": self.total_subdomain,
+            "host_counts": &self.host_counts,
+            "locales": self
+                .locales
+                .iter()
+                .map(|(locale, count)| serde_json::json!([locale, count]))
+                .collect::<Vec<_>>(),
+            "alternates": &self.alternates,
+            "top_external_domains": self
+                .top_external_domains
+                .iter()
+                .map(|(domain, count)| serde_json::json!([domain, count]))
+                .collect::<Vec<_>>(),
+            "region_counts": self
+                .region_counts
+                .iter()
+                .map(|(region, count)| {
+                    (region.as_str().to_string(), serde_json::json!(count))
+                })
+                .collect::<serde_json::Map<_, _>>(),
+            "rel_counts": self
+                .rel_counts
+                .iter()
+                .map(|(token, count)| {
+                    (token.as_str().to_string(), serde_json::json!(count))
+                })
+                .collect::<serde_json::Map<_, _>>(),
+            "attribute_counts": self
+                .attribute_counts
+                .iter()
+                .map(|(name, count)| (name.to_string(), serde_json::json!(count)))
+                .collect::<serde_json::Map<_, _>>(),
             "links": links,
             "groups": &self.groups,
+            "selector_groups": &self.selector_groups,
+            "url_templates": &self.url_templates,
+            "query_params": &self.query_params,
+            "pagination": &self.pagination,
+            "followed": &self.followed,
             "depth_distribution": depth_distribution,
             "utility_urls": &self.utility_urls,
         });
 
+        if let Some(occurrences) = &self.occurrences {
+            let map = obj.as_object_mut().unwrap();
+            map.remove("links");
+            map.insert("occurrences".to_string(), serde_json::json!(occurrences));
+        }
         if self.filter == LinkFilter::Internal {
             obj.as_object_mut().unwrap().remove("total_external");
         }
@@ -250,21 +744,84 @@ impl LinksOutput {
     }
 }
 
+fn occurrences_table(occurrences: &[LinkOccurrence]) -> Table {
+    let mut table = Table::new();
+    table.set_content_arrangement(ContentArrangement::Dynamic);
+    table.load_preset(UTF8_FULL_CONDENSED);
+    table.set_header(vec![
+        Cell::new("First")
+            .add_attribute(Attribute::Bold)
+            .set_alignment(CellAlignment::Right),
+        Cell::new("Type").add_attribute(Attribute::Bold),
+        Cell::new("URL").add_attribute(Attribute::Bold),
+        Cell::new("Count")
+            .add_attribute(Attribute::Bold)
+            .set_alignment(CellAlignment::Right),
+        Cell::new("Image")
+            .add_attribute(Attribute::Bold)
+            .set_alignment(CellAlignment::Right),
+        Cell::new("Texts").add_attribute(Attribute::Bold),
+    ]);
+    for occurrence in occurrences {
+        table.add_row(vec![
+            Cell::new(occurrence.first_position)
+                .set_alignment(CellAlignment::Right),
+            Cell::new(occurrence.scope.as_str()),
+            Cell::new(&occurrence.url),
+            Cell::new(occurrence.count).set_alignment(CellAlignment::Right),
+            Cell::new(occurrence.image_links).set_alignment(CellAlignment::Right),
+            Cell::new(occurrence.texts.join(" | ")),
+        ]);
+    }
+    table
+}
+
 impl RenderOutput for LinksOutput {
     fn render_text(&self) -> String {
         let mut out = String::new();
 
         out.push_str("\n## Links\n");
         out.push_str(&format!("URL: {}\n", self.url));
+        if let Some(base) = &self.base {
+            out.push_str(&format!("Base: {base}\n"));
+        }
         out.push_str(&format!("Filter: {}\n", self.filter.as_str()));
+        if !self.elements.is_empty() {
+            let elements: Vec<&str> = self
+                .elements
+                .iter()
+                .map(|element| element.as_str())
+                .collect();
+            out.push_str(&format!("Elements: {}\n", elements.join(", ")));
+        }
+        if !self.regions.is_empty() {
+            let regions: Vec<&str> =
+                self.regions.iter().map(|region| region.as_str()).collect();
+            out.push_str(&format!("Regions: {}\n", regions.join(", ")));
+        }
         out.push_str(&format!("Internal: {}\n", self.total_internal));
         out.push_str(&format!("External: {}\n", self.total_external));
+        out.push_str(&format!(
+            "Same host: {}, subdomains: {}\n",
+            self.total_same_host, self.total_subdomain
+        ));
+        if !self.locales.is_empty() {
+            let locales: Vec<String> = self
+                .locales
+                .iter()
+                .map(|(locale, count)| format!("{locale} ({count})"))
+                .collect();
+            out.push_str(&format!("Locales: {}\n", locales.join(", ")));
+        }
 
         let mut links_table = Table::new();
         links_table.set_content_arrangement(ContentArrangement::Dynamic);
         links_table.load_preset(UTF8_FULL_CONDENSED);
         links_table.set_header(vec![
             Cell::new("Type").add_attribute(Attribute::Bold),
+            Cell::new("Kind").add_attribute(Attribute::Bold),
+            Cell::new("Source").add_attribute(Attribute::Bold),
+            Cell::new("Region").add_attribute(Attribute::Bold),
             Cell::new("URL").add_attribute(Attribute::Bold),
             Cell::new("Raw").add_attribute(Attribute::Bold),
             Cell::new("Text").add_attribute(Attribute::Bold),
@@ -273,11 +830,10 @@ impl RenderOutput for LinksOutput {
 
         for link in &self.links {
             links_table.add_row(vec![
-                Cell::new(if link.is_internal {
-                    "internal"
-                } else {
-                    "external"
-                }),
+                Cell::new(link.scope.as_str()),
+                Cell::new(link.kind.as_str()),
+                Cell::new(format!("{}[{}]", link.element.as_str(), link.attribute)),
+                Cell::new(link.region.as_str()),
                 Cell::new(link.url.as_str()),
                 Cell::new(&link.raw_url),
                 Cell::new(link.text.as_deref().unwrap_or("")),
@@ -287,11 +843,128 @@ impl RenderOutput for LinksOutput {
 
         if self.links.is_empty() {
             out.push_str("(no links matched)\n");
+        } else if let Some(occurrences) = &self.occurrences {
+            out.push_str(&occurrences_table(occurrences).to_string());
+            out.push('\n');
         } else {
             out.push_str(&links_table.to_string());
             out.push('\n');
         }
 
+        if !self.rel_counts.is_empty() || !self.attribute_counts.is_empty() {
+            out.push_str("\n## Rel and Attributes\n");
+            let mut attribute_table = Table::new();
+            attribute_table.set_content_arrangement(ContentArrangement::Dynamic);
+            attribute_table.load_preset(UTF8_FULL_CONDENSED);
+            attribute_table.set_header(vec![
+                Cell::new("Attribute").add_attribute(Attribute::Bold),
+                Cell::new("Links")
+                    .add_attribute(Attribute::Bold)
+                    .set_alignment(CellAlignment::Right),
+            ]);
+            let rows = self
+                .rel_counts
+                .iter()
+                .map(|(token, count)| (format!("rel={}", token.as_str()), count))
+                .chain(
+                    self.attribute_counts
+                        .iter()
+                        .map(|(name, count)| (name.to_string(), count)),
+                );
+            for (name, count) in rows {
+                attribute_table.add_row(vec![
+                    Cell::new(name),
+                    Cell::new(count).set_alignment(CellAlignment::Right),
+                ]);
+            }
+            out.push_str(&attribute_table.to_string());
+            out.push('\n');
+        }
+
+        if !self.host_counts.is_empty() {
+            out.push_str("\n## Hosts\n");
+            let mut host_table = Table::new();
+            host_table.set_content_arrangement(ContentArrangement::Dynamic);
+            host_table.load_preset(UTF8_FULL_CONDENSED);
+            host_table.set_header(vec![
+                Cell::new("Host").add_attribute(Attribute::Bold),
+                Cell::new("Scope").add_attribute(Attribute::Bold),
+                Cell::new("Links")
+                    .add_attribute(Attribute::Bold)
+                    .set_alignment(CellAlignment::Right),
+            ]);
+            for host in &self.host_counts {
+                host_table.add_row(vec![
+                    Cell::new(&host.host),
+                    Cell::new(host.scope.as_str()),
+                    Cell::new(host.count).set_alignment(CellAlignment::Right),
+                ]);
+            }
+            out.push_str(&host_table.to_string());
+            out.push('\n');
+        }
+
+        if !self.top_external_domains.is_empty() {
+            out.push_str("\n## External Domains\n");
+            let mut domain_table = Table::new();
+            domain_table.set_content_arrangement(ContentArrangement::Dynamic);
+            domain_table.load_preset(UTF8_FULL_CONDENSED);
+            domain_table.set_header(vec![
+                Cell::new("Domain").add_attribute(Attribute::Bold),
+                Cell::new("Links")
+                    .add_attribute(Attribute::Bold)
+                    .set_alignment(CellAlignment::Right),
+            ]);
+            for (domain, count) in &self.top_external_domains {
+                domain_table.add_row(vec![
+                    Cell::new(domain),
+                    Cell::new(count).set_alignment(CellAlignment::Right),
+                ]);
+            }
+            out.push_str(&domain_table.to_string());
+            out.push('\n');
+        }
+
+        if !self.alternates.is_empty() {
+            out.push_str("\n## Hreflang Alternates\n");
+            let mut alternate_table = Table::new();
+            alternate_table.set_content_arrangement(ContentArrangement::Dynamic);
+            alternate_table.load_preset(UTF8_FULL_CONDENSED);
+            alternate_table.set_header(vec![
+                Cell::new("Hreflang").add_attribute(Attribute::Bold),
+                Cell::new("URL").add_attribute(Attribute::Bold),
+            ]);
+            for alternate in &self.alternates {
+                alternate_table.add_row(vec![
+                    Cell::new(&alternate.hreflang),
+                    Cell::new(&alternate.url),
+                ]);
+            }
+            out.push_str(&alternate_table.to_string());
+            out.push('\n');
+        }
+
+        if !self.region_counts.is_empty() {
+            out.push_str("\n## Regions\n");
+            let mut region_table = Table::new();
+            region_table.set_content_arrangement(ContentArrangement::Dynamic);
+            region_table.load_preset(UTF8_FULL_CONDENSED);
+            region_table.set_header(vec![
+                Cell::new("Region").add_attribute(Attribute::Bold),
+                Cell::new("Links")
+                    .add_attribute(Attribute::Bold)
+                    .set_alignment(CellAlignment::Right),
+            ]);
+            for (region, count) in &self.region_counts {
+                region_table.add_row(vec![
+                    Cell::new(region.as_str()),
+                    Cell::new(count).set_alignment(CellAlignment::Right),
+                ]);
+            }
+            out.push_str(&region_table.to_string());
+            out.push('\n');
+        }
+
         if !self.groups.is_empty() {
             out.push_str("\n## URL Groups\n");
 
@@ -303,14 +976,32 @@ impl RenderOutput for LinksOutput {
                 Cell::new("Links")
                     .add_attribute(Attribute::Bold)
                     .set_alignment(CellAlignment::Right),
+                Cell::new("Avg Anchor")
+                    .add_attribute(Attribute::Bold)
+                    .set_alignment(CellAlignment::Right),
+                Cell::new("Empty")
+                    .add_attribute(Attribute::Bold)
+                    .set_alignment(CellAlignment::Right),
+                Cell::new("Image")
+                    .add_attribute(Attribute::Bold)
+                    .set_alignment(CellAlignment::Right),
                 Cell::new("Sample URLs").add_attribute(Attribute::Bold),
+                Cell::new("Anchor Texts").add_attribute(Attribute::Bold),
             ]);
 
             for group in &self.groups {
+                let anchors = &group.anchors;
                 sections_table.add_row(vec![
                     Cell::new(&group.section),
                     Cell::new(group.count).set_alignment(CellAlignment::Right),
+                    Cell::new(format!("{:.1}", anchors.avg_len))
+                        .set_alignment(CellAlignment::Right),
+                    Cell::new(format!("{:.0}%", anchors.empty_share * 100.0))
+                        .set_alignment(CellAlignment::Right),
+                    Cell::new(format!("{:.0}%", anchors.image_share * 100.0))
+                        .set_alignment(CellAlignment::Right),
                     Cell::new(group.samples.join("\n")),
+                    Cell::new(anchors.samples.join("\n")),
                 ]);
             }
 
@@ -318,6 +1009,132 @@ impl RenderOutput for LinksOutput {
             out.push('\n');
         }
 
+        if !self.url_templates.is_empty() {
+            out.push_str("\n## URL Templates\n");
+            let mut template_table = Table::new();
+            template_table.set_content_arrangement(ContentArrangement::Dynamic);
+            template_table.load_preset(UTF8_FULL_CONDENSED);
+            template_table.set_header(vec![
+                Cell::new("Template").add_attribute(Attribute::Bold),
+                Cell::new("URLs")
+                    .add_attribute(Attribute::Bold)
+                    .set_alignment(CellAlignment::Right),
+                Cell::new("Sample Paths").add_attribute(Attribute::Bold),
+            ]);
+            for template in &self.url_templates {
+                template_table.add_row(vec![
+                    Cell::new(&template.template),
+                    Cell::new(template.count).set_alignment(CellAlignment::Right),
+                    Cell::new(template.samples.join("\n")),
+                ]);
+            }
+            out.push_str(&template_table.to_string());
+            out.push('\n');
+        }
+
+        if let Some(pagination) = &self.pagination {
+            out.push_str("\n## Pagination\n");
+            out.push_str(&format!("Scheme: {}\n", pagination.scheme.as_str()));
+            if let Some(param) = &pagination.param {
+                out.push_str(&format!("Param: {param}\n"));
+            }
+            if let Some(next) = &pagination.next {
+                out.push_str(&format!("Next: {next}\n"));
+            }
+            if let Some(prev) = &pagination.prev {
+                out.push_str(&format!("Prev: {prev}\n"));
+            }
+        }
+
+        if !self.followed.is_empty() {
+            out.push_str("\n## Followed Pages\n");
+            let mut followed_table = Table::new();
+            followed_table.set_content_arrangement(ContentArrangement::Dynamic);
+            followed_table.load_preset(UTF8_FULL_CONDENSED);
+            followed_table.set_header(vec![
+                Cell::new("Page")
+                    .add_attribute(Attribute::Bold)
+                    .set_alignment(CellAlignment::Right),
+                Cell::new("URL").add_attribute(Attribute::Bold),
+                Cell::new("Links")
+                    .add_attribute(Attribute::Bold)
+                    .set_alignment(CellAlignment::Right),
+                Cell::new("New")
+                    .add_attribute(Attribute::Bold)
+                    .set_alignment(CellAlignment::Right),
+            ]);
+            for (index, page) in self.followed.iter().enumerate() {
+                followed_table.add_row(vec![
+                    Cell::new(index + 1).set_alignment(CellAlignment::Right),
+                    Cell::new(&page.url),
+                    Cell::new(page.links).set_alignment(CellAlignment::Right),
+                    Cell::new(page.new_links).set_alignment(CellAlignment::Right),
+                ]);
+            }
+            out.push_str(&followed_table.to_string());
+            out.push('\n');
+            for page in &self.followed {
+                if let Some(error) = &page.error {
+                    out.push_str(&format!("Stopped at {}: {error}\n", page.url));
+                }
+            }
+        }
+
+        if !self.query_params.is_empty() {
+            out.push_str("\n## Query Parameters\n");
+            let mut param_table = Table::new();
+            param_table.set_content_arrangement(ContentArrangement::Dynamic);
+            param_table.load_preset(UTF8_FULL_CONDENSED);
+            param_table.set_header(vec![
+                Cell::new("Param").add_attribute(Attribute::Bold),
+                Cell::new("URLs")
+                    .add_attribute(Attribute::Bold)
+                    .set_alignment(CellAlignment::Right),
+                Cell::new("Values")
+                    .add_attribute(Attribute::Bold)
+                    .set_alignment(CellAlignment::Right),
+                Cell::new("Type").add_attribute(Attribute::Bold),
+                Cell::new("Role").add_attribute(Attribute::Bold),
+                Cell::new("Samples").add_attribute(Attribute::Bold),
+            ]);
+            for param in &self.query_params {
+                param_table.add_row(vec![
+                    Cell::new(&param.name),
+                    Cell::new(param.count).set_alignment(CellAlignment::Right),
+                    Cell::new(param.distinct_values)
+                        .set_alignment(CellAlignment::Right),
+                    Cell::new(param.value_type.as_str()),
+                    Cell::new(param.role.map(|role| role.as_str()).unwrap_or("")),
+                    Cell::new(param.samples.join(", ")),
+                ]);
+            }
+            out.push_str(&param_table.to_string());
+            out.push('\n');
+        }
+
+        if !self.selector_groups.is_empty() {
+            out.push_str("\n## Selectors\n");
+            let mut selector_table = Table::new();
+            selector_table.set_content_arrangement(ContentArrangement::Dynamic);
+            selector_table.load_preset(UTF8_FULL_CONDENSED);
+            selector_table.set_header(vec![
+                Cell::new("Selector").add_attribute(Attribute::Bold),
+                Cell::new("Links")
+                    .add_attribute(Attribute::Bold)
+                    .set_alignment(CellAlignment::Right),
+                Cell::new("Sample URLs").add_attribute(Attribute::Bold),
+            ]);
+            for group in &self.selector_groups {
+                selector_table.add_row(vec![
+                    Cell::new(&group.selector),
+                    Cell::new(group.count).set_alignment(CellAlignment::Right),
+                    Cell::new(group.samples.join("\n")),
+                ]);
+            }
+            out.push_str(&selector_table.to_string());
+            out.push('\n');
+        }
+
         if !self.depth_distribution.is_empty() {
             out.push_str("\n## Path Depth\n");
             let mut depth_table = Table::new();
@@ -361,6 +1178,9 @@ impl RenderOutput for LinksOutput {
 #[derive(Debug, Clone, Default)]
 pub struct LinkOptions {
     pub normalize: bool,
+    /// Extra rules applied when `normalize` is set.
+    pub url_policy: NormalizePolicy,
+    /// Drop `url_policy.tracking_params` even when `normalize` is off.
     pub strip_tracking_params: bool,
     pub max: usize,
 }
@@ -368,6 +1188,9 @@ pub struct LinkOptions {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::analyzer::pagination::PaginationScheme;
+    use crate::analyzer::query_params::{ParamRole, ParamValueType};
+    use crate::normalize::TrackingParams;
 
     fn make_link(url: &str, is_internal: bool) -> Link {
         Link {
@@ -375,7 +1198,30 @@ mod tests {
             url: Url::parse(url).unwrap(),
             text: None,
             rel: None,
+            rel_tokens: Vec::new(),
+            target: None,
+            hreflang: None,
+            media_type: None,
+            download: None,
+            title: None,
             is_internal,
+            scope: if is_internal {
+                LinkScope::SameHost
+            } else {
+                LinkScope::External
+            },
+            kind: link_kind::link_kind(
+                &Url::parse(url).unwrap(),
+                LinkElement::A,
+                None,
+                None,
+            ),
+            element: LinkElement::A,
+            attribute: "href".to_string(),
+            region: LinkRegion::Other,
+            selector: "body > a".to_string(),
+            generalized_selector: "body > a".to_string(),
+            image_only: false,
         }
     }
 
@@ -434,6 +1280,256 @@ mod tests {
         assert_eq!(links[2].href, "https://other.com/page");
     }
 
+    #[test]
+    fn extract_raw_links_covers_all_link_bearing_elements() {
+        let html = r#"<html><head>
+            <link rel="canonical" href="/canonical">
+            <script src="/app.js"></script>
+        </head><body>
+            <map><area href="/region" alt="Region"></map>
+            <iframe src="https://video.example/embed"></iframe>
+            <img src="/a.png" srcset="/a-1x.png 1x, /a-2x.png 2x" alt="Photo">
+            <picture><source srcset="/b.webp"></picture>
+            <form action="/search"></form>
+            <div data-href="/card" data-url="/card-alt">Card</div>
+        </body></html>"#;
+        let doc = Html::parse_document(html);
+        let found: Vec<(LinkElement, String, String)> = extract_raw_links(&doc)
+            .into_iter()
+            .map(|raw| (raw.element, raw.attribute, raw.href))
+            .collect();
+
+        let expected = [
+            (LinkElement::Link, "href", "/canonical"),
+            (LinkElement::Script, "src", "/app.js"),
+            (LinkElement::Area, "href", "/region"),
+            (LinkElement::Iframe, "src", "https://video.example/embed"),
+            (LinkElement::Img, "src", "/a.png"),
+            (LinkElement::Img, "srcset", "/a-1x.png"),
+            (LinkElement::Img, "srcset", "/a-2x.png"),
+            (LinkElement::Source, "srcset", "/b.webp"),
+            (LinkElement::Form, "action", "/search"),
+            (LinkElement::Data, "data-href", "/card"),
+            (LinkElement::Data, "data-url", "/card-alt"),
+        ];
+        let expected: Vec<(LinkElement, String, String)> = expected
+            .into_iter()
+            .map(|(kind, attr, href)| (kind, attr.to_string(), href.to_string()))
+            .collect();
+        assert_eq!(found, expected);
+
+        let raw = extract_raw_links(&doc);
+        assert_eq!(raw[0].rel.as_deref(), Some("canonical"));
+        assert_eq!(raw[4].text.as_deref(), Some("Photo"));
+    }
+
+    #[test]
+    fn links_query_filters_by_target_element_and_region() {
+        let mut img = make_link("https://cdn.other.com/a.png", false);
+        img.element = LinkElement::Img;
+        let page = make_link("https://example.com/page", true);
+
+        let query = LinksQuery {
+            filter: LinkFilter::All,
+            elements: vec![LinkElement::Img],
+            ..Default::default()
+        };
+        assert!(query.matches(&img));
+        assert!(!query.matches(&page));
+
+        let query = LinksQuery {
+            filter: LinkFilter::Internal,
+            ..Default::default()
+        };
+        assert!(!query.matches(&img));
+        assert!(query.matches(&page));
+
+        let mut nav = make_link("https://example.com/about", true);
+        nav.region = LinkRegion::Nav;
+        let query = LinksQuery {
+            regions: vec![LinkRegion::Nav],
+            ..Default::default()
+        };
+        assert!(query.matches(&nav));
+        assert!(!query.matches(&page));
+
+        let html = r#"<main><article><a href="/post">Post</a></article>
+            <a href="/more">More</a></main><aside><a href="/ad">Ad</a></aside>"#;
+        let doc = Html::parse_document(html);
+        let links =
+            extract_links(&doc, &Url::parse("https://example.com/").unwrap());
+        let query = LinksQuery {
+            regions: vec![LinkRegion::Main],
+            ..Default::default()
+        };
+        let kept: Vec<&str> = links
+            .iter()
+            .filter(|link| query.matches(link))
+            .map(|link| link.url.path())
+            .collect();
+        assert_eq!(kept, ["/post", "/more"]);
+        assert_eq!(LinkElement::parse("iframe"), Some(LinkElement::Iframe));
+        assert!(!LinkElement::Img.is_navigational());
+    }
+
+    #[test]
+    fn selector_groups_count_repeating_structures() {
+        let html = r#"<html><body><main><ul class="posts">
+            <li><a href="/p/1">One</a></li>
+            <li><a href="/p/2">Two</a></li>
+            <li><a href="/p/3">Three</a></li>
+            </ul></main><footer><a href="/privacy">Privacy</a></footer>
+            </body></html>"#;
+        let doc = Html::parse_document(html);
+        let base = Url::parse("https://example.com/").unwrap();
+        let links = extract_links(&doc, &base);
+
+        assert_eq!(links[2].selector, "main > ul.posts > li:nth-of-type(3) > a");
+        let groups = selector_groups(&links);
+        assert_eq!(groups[0].selector, "main > ul.posts > li > a");
+        assert_eq!(groups[0].count, 3);
+        assert_eq!(groups[0].samples.len(), 3);
+        assert_eq!(groups[1].selector, "body > footer > a");
+        assert_eq!(groups[1].count, 1);
+    }
+
+    #[test]
+    fn extract_raw_links_flags_image_only_anchors() {
+        let html = r#"<a href="/a"><img src="/logo.png" alt="Logo"></a>
+            <a href="/b"><svg></svg> Home</a><a href="/c"></a>"#;
+        let doc = Html::parse_document(html);
+        let anchors: Vec<RawLink> = extract_raw_links(&doc)
+            .into_iter()
+            .filter(|link| link.element == LinkElement::A)
+            .collect();
+        assert!(anchors[0].image_only);
+        assert!(!anchors[1].image_only);
+        assert!(!anchors[2].image_only);
+    }
+
+    #[test]
+    fn extract_links_parses_rel_tokens_and_attributes() {
+        let html = r#"<a href="/a" rel="nofollow sponsored" target="_blank"
+                title=" About us ">A</a>
+            <a href="/guide.pdf" type="application/pdf" download>Guide</a>
+            <link rel="alternate" hreflang="de" href="/de/">"#;
+        let doc = Html::parse_document(html);
+        let page = Url::parse("https://example.com/").unwrap();
+        let links = extract_links(&doc, &page);
+
+        assert_eq!(
+            links[0].rel_tokens,
+            [RelToken::Nofollow, RelToken::Sponsored]
+        );
+        assert!(links[0].has_rel(RelToken::Nofollow));
+        assert_eq!(links[0].target.as_deref(), Some("_blank"));
+        assert_eq!(links[0].title.as_deref(), Some("About us"));
+        assert_eq!(links[1].media_type.as_deref(), Some("application/pdf"));
+        assert_eq!(links[1].download.as_deref(), Some(""));
+        assert_eq!(links[2].hreflang.as_deref(), Some("de"));
+
+        assert_eq!(
+            rel_counts(&links),
+            [
+                (RelToken::Nofollow, 1),
+                (RelToken::Sponsored, 1),
+                (RelToken::Alternate, 1)
+            ]
+        );
+        assert_eq!(
+            attribute_counts(&links),
+            [
+                ("target=_blank", 1),
+                ("hreflang", 1),
+                ("type", 1),
+                ("download", 1),
+                ("title", 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn extract_links_classifies_kinds() {
+        let html = r#"<link rel="stylesheet" href="/site">
+            <a href="/about">About</a><a href="/r.pdf">Report</a>
+            <a href="mailto:hi@example.com">Mail</a><img src="/pixel">"#;
+        let doc = Html::parse_document(html);
+        let page = Url::parse("https://example.com/").unwrap();
+        let links = extract_links(&doc, &page);
+        let kinds: Vec<LinkKind> = links.iter().map(|link| link.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                LinkKind::Stylesheet,
+                LinkKind::Page,
+                LinkKind::Document,
+                LinkKind::Mailto,
+                LinkKind::Image
+            ]
+        );
+        assert!(links[2].is_asset());
+
+        let query = LinksQuery {
+            filter: LinkFilter::parse("document").unwrap(),
+            ..Default::default()
+        };
+        assert_eq!(query.filter.as_str(), "document");
+        assert_eq!(links.iter().filter(|link| query.matches(link)).count(), 1);
+    }
+
+    #[test]
+    fn dedupe_links_counts_occurrences_and_texts() {
+        let html = r#"<a href="/story"><img src="/t.jpg"></a>
+            <a href="/about">About</a>
+            <a href="/story#more">Big story</a>
+            <a href="/story">Read more</a>
+            <a href="/story">Big story</a>"#;
+        let doc = Html::parse_document(html);
+        let page = Url::parse("https://example.com/").unwrap();
+        let links: Vec<Link> = extract_links(&doc, &page)
+            .into_iter()
+            .filter(|link| link.element == LinkElement::A)
+            .collect();
+
+        let occurrences = dedupe_links(&links);
+        assert_eq!(occurrences.len(), 2);
+        let story = &occurrences[0];
+        assert_eq!(story.url, "https://example.com/story");
+        assert_eq!(story.count, 4);
+        assert_eq!(story.image_links, 1);
+        assert_eq!(story.texts, ["Big story", "Read more"]);
+        assert_eq!(story.first_position, 1);
+        assert_eq!(occurrences[1].first_position, 2);
+    }
+
+    #[test]
+    fn extract_links_separates_same_host_and_subdomains() {
+        let html = r#"<a href="/a">A</a><a href="https://blog.example.com/">Blog</a>
+            <a href="https://other.com/">Other</a>"#;
+        let doc = Html::parse_document(html);
+        let base = Url::parse("https://www.example.com/").unwrap();
+        let links = extract_links(&doc, &base);
+
+        let scopes: Vec<LinkScope> = links.iter().map(|link| link.scope).collect();
+        assert_eq!(
+            scopes,
+            [
+                LinkScope::SameHost,
+                LinkScope::Subdomain,
+                LinkScope::External
+            ]
+        );
+        assert!(links[1].is_internal);
+
+        let query = LinksQuery {
+            filter: LinkFilter::Subdomain,
+            ..Default::default()
+        };
+        assert!(query.matches(&links[1]));
+        assert!(!query.matches(&links[0]));
+        assert_eq!(LinkFilter::parse("same-host"), Some(LinkFilter::SameHost));
+    }
+
     #[test]
     fn extract_links_basic() {
         let html = r#"<html><body>
@@ -576,26 +1672,151 @@ mod tests {
         assert!(!opts.normalize);
         assert!(!opts.strip_tracking_params);
         assert_eq!(opts.max, 0);
+        assert_eq!(opts.url_policy, NormalizePolicy::default());
+    }
+
+    #[test]
+    fn extract_links_with_strips_configured_tracking_params() {
+        let html = r#"<a href="/p?id=1&ref=nav&mc_eid=x">P</a>"#;
+        let doc = Html::parse_document(html);
+        let base = Url::parse("https://example.com/").unwrap();
+        let opts = LinkOptions {
+            url_policy: NormalizePolicy {
+                tracking_params: TrackingParams::with_extra(&["ref"]),
+                ..Default::default()
+            },
+            strip_tracking_params: true,
+            ..Default::default()
+        };
+        let links = extract_links_with(&doc, &base, &opts);
+        assert_eq!(links[0].url.as_str(), "https://example.com/p?id=1");
+    }
+
+    #[test]
+    fn extract_links_with_policy_merges_url_variants() {
+        let html = r#"<html><body>
+            <a href="/docs/?b=2&a=1">Docs</a>
+            <a href="/docs/index.html?a=1&utm_source=x&b=2">Docs again</a>
+        </body></html>"#;
+        let doc = Html::parse_document(html);
+        let base = Url::parse("https://example.com/").unwrap();
+        let opts = LinkOptions {
+            normalize: true,
+            url_policy: NormalizePolicy::all(),
+            ..Default::default()
+        };
+        let links = extract_links_with(&doc, &base, &opts);
+
+        assert_eq!(links[0].url, links[1].url);
+        assert_eq!(links[0].url.as_str(), "https://example.com/docs?a=1&b=2");
+        assert_eq!(links[1].raw_url, "/docs/index.html?a=1&utm_source=x&b=2");
+    }
+
+    #[test]
+    fn extract_links_resolves_against_base_href() {
+        let html = r#"<html><head><base href="https://blog.other.com/en/">
+            </head><body><a href="post">Post</a><a href="/about">About</a>
+            </body></html>"#;
+        let doc = Html::parse_document(html);
+        let page = Url::parse("https://example.com/app/page").unwrap();
+        let links = extract_links(&doc, &page);
+
+        assert_eq!(links[0].url.as_str(), "https://blog.other.com/en/post");
+        assert_eq!(links[1].url.as_str(), "https://blog.other.com/about");
+        assert!(!links[0].is_internal);
+    }
+
+    #[test]
+    fn document_base_resolves_relative_and_ignores_non_http() {
+        let page = Url::parse("https://example.com/a/b").unwrap();
+        let relative = Html::parse_document(r#"<base href="/docs/">"#);
+        assert_eq!(
+            document_base(&relative, &page).unwrap().as_str(),
+            "https://example.com/docs/"
+        );
+
+        let script = Html::parse_document(r#"<base href="javascript:void(0)">"#);
+        assert!(document_base(&script, &page).is_none());
+        let target_only = Html::parse_document(r#"<base target="_blank">"#);
+        assert!(document_base(&target_only, &page).is_none());
     }
 
     #[test]
     fn links_output_json_includes_selected_facts() {
         let output = LinksOutput {
             url: "https://example.com/".to_string(),
+            base: Some("https://cdn.example.com/site/".to_string()),
             filter: LinkFilter::All,
+            elements: Vec::new(),
+            regions: Vec::new(),
             total_internal: 2,
             total_external: 1,
+            total_same_host: 2,
+            total_subdomain: 0,
+            host_counts: Vec::new(),
+            top_external_domains: vec![("other.com".to_string(), 1)],
+            region_counts: vec![(LinkRegion::Main, 2), (LinkRegion::Footer, 1)],
+            locales: Vec::new(),
+            alternates: Vec::new(),
+            rel_counts: Vec::new(),
+            attribute_counts: Vec::new(),
             links: vec![Link {
                 raw_url: "/docs".to_string(),
                 url: Url::parse("https://example.com/docs").unwrap(),
                 text: Some("Docs".to_string()),
                 rel: None,
+                rel_tokens: Vec::new(),
+                target: None,
+                hreflang: None,
+                media_type: None,
+                download: None,
+                title: None,
                 is_internal: true,
+                scope: LinkScope::SameHost,
+                kind: LinkKind::Page,
+                element: LinkElement::A,
+                attribute: "href".to_string(),
+                region: LinkRegion::Other,
+                selector: "body > a".to_string(),
+                generalized_selector: "body > a".to_string(),
+                image_only: false,
             }],
+            occurrences: None,
             groups: vec![LinkGroup {
                 section: "docs".to_string(),
                 count: 2,
                 samples: vec!["/docs".to_string()],
+                anchors: AnchorStats {
+                    samples: vec!["Docs".to_string()],
+                    avg_len: 4.0,
+                    empty_share: 0.5,
+                    image_share: 0.0,
+                },
+            }],
+            selector_groups: vec![SelectorGroup {
+                selector: "main > ul > li > a".to_string(),
+                count: 2,
+                samples: vec!["https://example.com/docs".to_string()],
+            }],
+            url_templates: vec![UrlTemplate {
+                template: "/docs/{slug}".to_string(),
+                count: 2,
+                samples: vec!["/docs/intro".to_string()],
+            }],
+            pagination: Some(Pagination {
+                scheme: PaginationScheme::RelNext,
+                next: Some("https://example.com/?page=2".to_string()),
+                prev: None,
+                param: Some("page".to_string()),
+            }),
+            followed: Vec::new(),
+            query_params: vec![QueryParam {
+                name: "page".to_string(),
+                count: 2,
+                distinct_values: 2,
+                samples: vec!["2".to_string(), "3".to_string()],
+                value_type: ParamValueType::Int,
+                role: Some(ParamRole::Pagination),
             }],
             depth_distribution: vec![(1, 2)],
             utility_urls: vec!["https://example.com/privacy".to_string()],
@@ -604,14 +1825,35 @@ mod tests {
         let parsed: serde_json::Value =
             serde_json::from_str(&output.render_json()).unwrap();
         assert_eq!(parsed["url"], "https://example.com/");
+        assert_eq!(parsed["base"], "https://cdn.example.com/site/");
         assert_eq!(parsed["filter"], "all");
         assert_eq!(parsed["total_internal"], 2);
         assert_eq!(parsed["total_external"], 1);
+        assert_eq!(parsed["total_same_host"], 2);
+        assert_eq!(parsed["top_external_domains"][0][0], "other.com");
         assert_eq!(parsed["links"][0]["raw_url"], "/docs");
         assert_eq!(parsed["links"][0]["url"], "https://example.com/docs");
         assert_eq!(parsed["links"][0]["text"], "Docs");
         assert_eq!(parsed["links"][0]["is_internal"], true);
+        assert_eq!(parsed["links"][0]["element"], "a");
+        assert_eq!(parsed["links"][0]["attribute"], "href");
+        assert_eq!(parsed["links"][0]["region"], "other");
+        assert_eq!(parsed["region_counts"]["main"], 2);
         assert_eq!(parsed["groups"][0]["section"], "docs");
+        assert_eq!(parsed["groups"][0]["anchors"]["samples"][0], "Docs");
+        assert_eq!(parsed["groups"][0]["anchors"]["empty_share"], 0.5);
+        assert_eq!(parsed["links"][0]["selector"], "body > a");
+        assert_eq!(
+            parsed["selector_groups"][0]["selector"],
+            "main > ul > li > a"
+        );
+        assert_eq!(parsed["selector_groups"][0]["count"], 2);
+        assert_eq!(parsed["url_templates"][0]["template"], "/docs/{slug}");
+        assert_eq!(parsed["query_params"][0]["name"], "page");
+        assert_eq!(parsed["query_params"][0]["value_type"], "int");
+        assert_eq!(parsed["query_params"][0]["role"], "pagination");
+        assert_eq!(parsed["pagination"]["scheme"], "rel_next");
+        assert_eq!(parsed["pagination"]["param"], "page");
         assert_eq!(parsed["depth_distribution"][0][0], 1);
     }
 
@@ -619,11 +1861,29 @@ mod tests {
     fn links_output_toon_uses_same_value() {
         let output = LinksOutput {
             url: "https://example.com/".to_string(),
+            base: None,
             filter: LinkFilter::Internal,
+            elements: vec![LinkElement::A],
+            regions: vec![LinkRegion::Main],
             total_internal: 2,
             total_external: 1,
+            total_same_host: 2,
+            total_subdomain: 0,
+            host_counts: Vec::new(),
+            top_external_domains: vec![("other.com".to_string(), 1)],
+            region_counts: Vec::new(),
+            locales: Vec::new(),
+            alternates: Vec::new(),
+            rel_counts: Vec::new(),
+            attribute_counts: Vec::new(),
             links: Vec::new(),
+            occurrences: None,
             groups: Vec::new(),
+            selector_groups: Vec::new(),
+            url_templates: Vec::new(),
+            pagination: None,
+            followed: Vec::new(),
+            query_params: Vec::new(),
             depth_distribution: Vec::new(),
             utility_urls: Vec::new(),
         };
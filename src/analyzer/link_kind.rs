@@ -0,0 +1,265 @@
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::analyzer::link::LinkElement;
+
+/// What a link points at, guessed from its scheme, `type` attribute, source
+/// element, and file extension.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkKind {
+    /// An HTML page, including extensionless paths.
+    Page,
+    Image,
+    Stylesheet,
+    Script,
+    Font,
+    /// Audio and video.
+    Media,
+    /// PDF, office documents, plain text.
+    Document,
+    Archive,
+    /// RSS, Atom, and JSON feeds.
+    Feed,
+    Mailto,
+    Tel,
+    Javascript,
+    /// Other schemes (`data:`, `ftp:`) and unknown file types.
+    Other,
+}
+
+impl LinkKind {
+    pub const ALL: [LinkKind; 13] = [
+        LinkKind::Page,
+        LinkKind::Image,
+        LinkKind::Stylesheet,
+        LinkKind::Script,
+        LinkKind::Font,
+        LinkKind::Media,
+        LinkKind::Document,
+        LinkKind::Archive,
+        LinkKind::Feed,
+        LinkKind::Mailto,
+        LinkKind::Tel,
+        LinkKind::Javascript,
+        LinkKind::Other,
+    ];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.as_str() == value)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Page => "page",
+            Self::Image => "image",
+            Self::Stylesheet => "stylesheet",
+            Self::Script => "script",
+            Self::Font => "font",
+            Self::Media => "media",
+            Self::Document => "document",
+            Self::Archive => "archive",
+            Self::Feed => "feed",
+            Self::Mailto => "mailto",
+            Self::Tel => "tel",
+            Self::Javascript => "javascript",
+            Self::Other => "other",
+        }
+    }
+
+    /// Files fetched or downloaded rather than browsed.
+    pub fn is_asset(self) -> bool {
+        matches!(
+            self,
+            Self::Image
+                | Self::Stylesheet
+                | Self::Script
+                | Self::Font
+                | Self::Media
+                | Self::Document
+                | Self::Archive
+        )
+    }
+}
+
+/// Classifies a resolved link. The `type` attribute wins over the element,
+/// which wins over the file extension; `rel` spots `<link>` stylesheets and
+/// icons.
+pub fn link_kind(
+    url: &Url,
+    element: LinkElement,
+    rel: Option<&str>,
+    media_type: Option<&str>,
+) -> LinkKind {
+    match url.scheme() {
+        "http" | "https" => {}
+        "mailto" => return LinkKind::Mailto,
+        "tel" => return LinkKind::Tel,
+        "javascript" => return LinkKind::Javascript,
+        _ => return LinkKind::Other,
+    }
+    if let Some(kind) = media_type.and_then(kind_from_mime) {
+        return kind;
+    }
+    let by_extension = kind_from_extension(url.path());
+    let has_rel = |token: &str| {
+        rel.is_some_and(|rel| {
+            rel.split_ascii_whitespace()
+                .any(|value| value.eq_ignore_ascii_case(token))
+        })
+    };
+    match element {
+        LinkElement::Img => LinkKind::Image,
+        LinkElement::Script => LinkKind::Script,
+        LinkElement::Source => match by_extension {
+            Some(LinkKind::Image) => LinkKind::Image,
+            _ => LinkKind::Media,
+        },
+        LinkElement::Link if has_rel("stylesheet") => LinkKind::Stylesheet,
+        LinkElement::Link if has_rel("icon") || has_rel("apple-touch-icon") => {
+            LinkKind::Image
+        }
+        _ => by_extension.unwrap_or(LinkKind::Page),
+    }
+}
+
+/// Kind for a MIME type such as `image/png` or `application/rss+xml`.
+pub fn kind_from_mime(mime: &str) -> Option<LinkKind> {
+    let mime = mime
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    let (top, sub) = mime.split_once('/')?;
+    let kind = match (top, sub) {
+        ("text", "html") | ("application", "xhtml+xml") => LinkKind::Page,
+        ("text", "css") => LinkKind::Stylesheet,
+        (_, "javascript" | "ecmascript" | "x-javascript") => LinkKind::Script,
+        ("module", _) => LinkKind::Script,
+        ("image", _) => LinkKind::Image,
+        ("font", _) | ("application", "font-woff" | "vnd.ms-fontobject") => {
+            LinkKind::Font
+        }
+        ("audio" | "video", _) => LinkKind::Media,
+        ("application", "rss+xml" | "atom+xml" | "feed+json") => LinkKind::Feed,
+        ("application", "zip" | "gzip" | "x-tar" | "x-7z-compressed")
+        | ("application", "x-rar-compressed" | "x-bzip2") => LinkKind::Archive,
+        ("application", "pdf" | "msword" | "rtf") | ("text", "plain" | "csv") => {
+            LinkKind::Document
+        }
+        ("application", sub) if sub.starts_with("vnd.") => LinkKind::Document,
+        _ => return None,
+    };
+    Some(kind)
+}
+
+/// Kind for the extension of the last path segment; `None` without one.
+fn kind_from_extension(path: &str) -> Option<LinkKind> {
+    let file = path.rsplit('/').next().unwrap_or_default();
+    let (_, extension) = file.rsplit_once('.')?;
+    let kind = match extension.to_ascii_lowercase().as_str() {
+        "html" | "htm" | "xhtml" | "php" | "asp" | "aspx" | "jsp" | "shtml" => {
+            LinkKind::Page
+        }
+        "png" | "jpg" | "jpeg" | "gif" | "svg" | "webp" | "avif" | "ico"
+        | "bmp" | "tif" | "tiff" => LinkKind::Image,
+        "css" => LinkKind::Stylesheet,
+        "js" | "mjs" | "cjs" => LinkKind::Script,
+        "woff" | "woff2" | "ttf" | "otf" | "eot" => LinkKind::Font,
+        "mp4" | "webm" | "mov" | "m4v" | "mp3" | "ogg" | "wav" | "m4a" | "flac" => {
+            LinkKind::Media
+        }
+        "pdf" | "doc" | "docx" | "xls" | "xlsx" | "ppt" | "pptx" | "odt"
+        | "rtf" | "txt" | "csv" | "epub" => LinkKind::Document,
+        "zip" | "tar" | "gz" | "tgz" | "bz2" | "xz" | "7z" | "rar" => {
+            LinkKind::Archive
+        }
+        "rss" | "atom" => LinkKind::Feed,
+        _ => LinkKind::Other,
+    };
+    Some(kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kind(url: &str, element: LinkElement) -> LinkKind {
+        link_kind(&Url::parse(url).unwrap(), element, None, None)
+    }
+
+    #[test]
+    fn classifies_by_scheme_and_extension() {
+        assert_eq!(
+            kind("mailto:hi@example.com", LinkElement::A),
+            LinkKind::Mailto
+        );
+        assert_eq!(kind("tel:+123", LinkElement::A), LinkKind::Tel);
+        assert_eq!(
+            kind("javascript:void(0)", LinkElement::A),
+            LinkKind::Javascript
+        );
+        assert_eq!(kind("https://e.com/about", LinkElement::A), LinkKind::Page);
+        assert_eq!(kind("https://e.com/a.html", LinkElement::A), LinkKind::Page);
+        assert_eq!(
+            kind("https://e.com/r.PDF", LinkElement::A),
+            LinkKind::Document
+        );
+        assert_eq!(
+            kind("https://e.com/x.tar.gz", LinkElement::A),
+            LinkKind::Archive
+        );
+        assert_eq!(kind("https://e.com/v1.2/x", LinkElement::A), LinkKind::Page);
+        assert_eq!(
+            kind("https://e.com/data.json", LinkElement::A),
+            LinkKind::Other
+        );
+    }
+
+    #[test]
+    fn type_attribute_and_element_override_extension() {
+        let url = Url::parse("https://e.com/feed").unwrap();
+        assert_eq!(
+            link_kind(
+                &url,
+                LinkElement::Link,
+                Some("alternate"),
+                Some("application/rss+xml")
+            ),
+            LinkKind::Feed
+        );
+        let url = Url::parse("https://e.com/theme").unwrap();
+        assert_eq!(
+            link_kind(&url, LinkElement::Link, Some("Stylesheet"), None),
+            LinkKind::Stylesheet
+        );
+        assert_eq!(
+            kind("https://e.com/cdn/pixel", LinkElement::Img),
+            LinkKind::Image
+        );
+        assert_eq!(
+            kind("https://e.com/clip", LinkElement::Source),
+            LinkKind::Media
+        );
+        assert_eq!(
+            kind("https://e.com/app", LinkElement::Script),
+            LinkKind::Script
+        );
+        assert_eq!(
+            kind_from_mime("text/javascript; charset=utf-8"),
+            Some(LinkKind::Script)
+        );
+        assert!(LinkKind::Font.is_asset());
+        assert!(!LinkKind::Feed.is_asset());
+    }
+
+    #[test]
+    fn kinds_round_trip_through_names() {
+        for kind in LinkKind::ALL {
+            assert_eq!(LinkKind::parse(kind.as_str()), Some(kind));
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use std::collections::{BTreeSet, HashSet};
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::analyzer::link::{self, Link};
+use crate::analyzer::rel::RelToken;
+
+/// Languages accepted as a bare path segment (`/en/...`) or host label
+/// (`de.example.com`); `xx-yy` and `xx_YY` forms are accepted for these too.
+const LOCALES: &[&str] = &[
+    "ar", "bg", "bn", "cs", "da", "de", "el", "en", "es", "fa", "fi", "fr", "he",
+    "hi", "hu", "id", "it", "ja", "ko", "ms", "nl", "no", "pl", "pt", "ro", "ru",
+    "sk", "sv", "th", "tr", "uk", "vi", "zh",
+];
+
+/// One `<link rel="alternate" hreflang="...">` entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HreflangAlternate {
+    /// Language tag as written, e.g. `de-AT` or `x-default`.
+    pub hreflang: String,
+    pub url: String,
+}
+
+pub fn is_locale(value: &str) -> bool {
+    let (language, region) = match value.split_once(['-', '_']) {
+        Some((language, region)) => (language, Some(region)),
+        None => (value, None),
+    };
+    LOCALES.contains(&language.to_ascii_lowercase().as_str())
+        && region.is_none_or(|region| {
+            region.len() == 2 && region.bytes().all(|b| b.is_ascii_alphabetic())
+        })
+}
+
+/// Locale prefix of a path: `/ru_RU/news` -> `ru-ru`.
+pub fn path_locale(url: &Url) -> Option<String> {
+    let first = url.path_segments()?.find(|segment| !segment.is_empty())?;
+    is_locale(first).then(|| normalize_locale(first))
+}
+
+/// Path locale prefixes confirmed across one set of URLs. Codes such as `id`,
+/// `no`, or `hi` are also ordinary path segments (`/id/123`), so a prefix
+/// counts only with corroboration: a region (`en-us`), a second distinct
+/// locale prefix among the URLs, or an `hreflang` with its language.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PathLocales {
+    confirmed: HashSet<String>,
+}
+
+impl PathLocales {
+    pub fn detect<'a, 'b>(
+        urls: impl IntoIterator<Item = &'a Url>,
+        hreflangs: impl IntoIterator<Item = &'b str>,
+    ) -> Self {
+        let candidates: BTreeSet<String> =
+            urls.into_iter().filter_map(path_locale).collect();
+        let languages: HashSet<String> = hreflangs
+            .into_iter()
+            .map(|hreflang| language(&normalize_locale(hreflang)).to_string())
+            .collect();
+        let several = candidates.len() > 1;
+        let confirmed = candidates
+            .into_iter()
+            .filter(|locale| {
+                several
+                    || locale.contains('-')
+                    || languages.contains(language(locale))
+            })
+            .collect();
+        Self { confirmed }
+    }
+
+    /// Whether `segment` is a confirmed locale prefix.
+    pub fn contains(&self, segment: &str) -> bool {
+        is_locale(segment) && self.confirmed.contains(&normalize_locale(segment))
+    }
+
+    /// Confirmed locale prefix of `url`'s path.
+    pub fn of(&self, url: &Url) -> Option<String> {
+        path_locale(url).filter(|locale| self.confirmed.contains(locale))
+    }
+
+    /// Locale from a confirmed path prefix, else from the host.
+    pub fn url_locale(&self, url: &Url) -> Option<String> {
+        self.of(url).or_else(|| host_locale(url))
+    }
+}
+
+/// Locale label of a host: `de.example.com` -> `de`. The registered domain
+/// itself never counts, so `it.com` is not Italian.
+pub fn host_locale(url: &Url) -> Option<String> {
+    let host = url.host_str()?;
+    let domain = link::extract_registered_domain(url)?;
+    let (label, _) = host.split_once('.')?;
+    (host != domain && is_locale(label)).then(|| normalize_locale(label))
+}
+
+/// `rel="alternate"` links carrying `hreflang`, in document order without
+/// duplicates.
+pub fn hreflang_alternates(links: &[Link]) -> Vec<HreflangAlternate> {
+    let mut alternates: Vec<HreflangAlternate> = Vec::new();
+    for link in links
+        .iter()
+        .filter(|link| link.has_rel(RelToken::Alternate))
+    {
+        let Some(hreflang) = &link.hreflang else {
+            continue;
+        };
+        let alternate = HreflangAlternate {
+            hreflang: hreflang.clone(),
+            url: link.url.to_string(),
+        };
+        if !alternates.contains(&alternate) {
+            alternates.push(alternate);
+        }
+    }
+    alternates
+}
+
+fn normalize_locale(value: &str) -> String {
+    value.to_ascii_lowercase().replace('_', "-")
+}
+
+/// `pt-br` -> `pt`
+fn language(locale: &str) -> &str {
+    locale.split('-').next().unwrap_or(locale)
+}
+
+#[cfg(test)]
+mod tests {
+    use dom_content_extraction::scraper::Html;
+
+    use super::*;
+
+    fn url(value: &str) -> Url {
+        Url::parse(value).unwrap()
+    }
+
+    #[test]
+    fn detects_path_and_host_locales() {
+        assert_eq!(
+            path_locale(&url("https://example.com/ru_RU/news")).as_deref(),
+            Some("ru-ru")
+        );
+        assert_eq!(path_locale(&url("https://example.com/news/en")), None);
+        assert_eq!(path_locale(&url("https://example.com/english/")), None);
+        assert_eq!(
+            host_locale(&url("https://de.example.com/")).as_deref(),
+            Some("de")
+        );
+        assert_eq!(host_locale(&url("https://it.com/")), None);
+        assert_eq!(host_locale(&url("https://www.example.com/")), None);
+        let urls = [
+            url("https://fr.example.com/en/"),
+            url("https://example.com/de/"),
+        ];
+        let locales = PathLocales::detect(&urls, []);
+        assert_eq!(locales.url_locale(&urls[0]).as_deref(), Some("en"));
+        assert_eq!(
+            PathLocales::default().url_locale(&urls[0]).as_deref(),
+            Some("fr")
+        );
+    }
+
+    #[test]
+    fn path_locales_need_corroboration() {
+        let ids = [
+            url("https://example.com/id/123"),
+            url("https://example.com/id/456"),
+        ];
+        let locales = PathLocales::detect(&ids, []);
+        assert_eq!(locales.of(&ids[0]), None);
+        assert!(!locales.contains("id"));
+
+        let locales = PathLocales::detect(&ids, ["id-ID"]);
+        assert_eq!(locales.of(&ids[0]).as_deref(), Some("id"));
+
+        let mixed = [
+            url("https://example.com/en/a"),
+            url("https://example.com/de/a"),
+        ];
+        let locales = PathLocales::detect(&mixed, []);
+        assert!(locales.contains("en") && locales.contains("de"));
+
+        let regional = [url("https://example.com/pt_BR/a")];
+        let locales = PathLocales::detect(&regional, []);
+        assert_eq!(locales.of(&regional[0]).as_deref(), Some("pt-br"));
+    }
+
+    #[test]
+    fn collects_hreflang_alternates() {
+        let html = r#"<link rel="alternate" hreflang="de-AT" href="/de-at/">
+            <link rel="alternate" hreflang="x-default" href="/">
+            <link rel="alternate" hreflang="de-AT" href="/de-at/">
+            <link rel="alternate" type="application/rss+xml" href="/feed">
+            <a href="/fr/" hreflang="fr">Français</a>"#;
+        let doc = Html::parse_document(html);
+        let links = link::extract_links(&doc, &url("https://example.com/"));
+        assert_eq!(
+            hreflang_alternates(&links),
+            [
+                HreflangAlternate {
+                    hreflang: "de-AT".to_string(),
+                    url: "https://example.com/de-at/".to_string(),
+                },
+                HreflangAlternate {
+                    hreflang: "x-default".to_string(),
+                    url: "https://example.com/".to_string(),
+                },
+            ]
+        );
+    }
+}
@@ -2,6 +2,7 @@ use dom_content_extraction::scraper::{Html, Selector};
 use serde::{Deserialize, Serialize};
 use std::sync::LazyLock;
 
+use crate::analyzer::locale::HreflangAlternate;
 use crate::output::RenderOutput;
 
 static META_SELECTOR: LazyLock<Selector> =
@@ -22,6 +23,7 @@ pub struct MetaOutput {
     pub lang: Option<String>,
     pub verbosity: MetaVerbosity,
     pub tags: Vec<MetaTag>,
+    pub alternates: Vec<HreflangAlternate>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -97,13 +99,14 @@ impl MetaOutput {
             "lang": &self.lang,
             "verbosity": self.verbosity.as_str(),
             "tags": &self.tags,
+            "alternates": &self.alternates,
         })
     }
 }
 
 impl RenderOutput for MetaOutput {
     fn render_text(&self) -> String {
-        if self.tags.is_empty() {
+        if self.tags.is_empty() && self.alternates.is_empty() {
             return String::new();
         }
 
@@ -124,6 +127,15 @@ impl RenderOutput for MetaOutput {
             let content = tag.content.as_deref().unwrap_or("");
             out.push_str(&format!("{source}\t{name}\t{content}\n"));
         }
+        if !self.alternates.is_empty() {
+            out.push_str("\nHreflang\tURL\n");
+            for alternate in &self.alternates {
+                out.push_str(&format!(
+                    "{}\t{}\n",
+                    alternate.hreflang, alternate.url
+                ));
+            }
+        }
         out
     }
 
@@ -3,18 +3,25 @@ use comfy_table::{Attribute, Cell, ContentArrangement, Table};
 use dom_content_extraction::scraper::{Html, Selector};
 use url::Url;
 
+use crate::analyzer::contacts::{self, ContactsOutput};
 use crate::analyzer::error::AnalyzerError;
 use crate::analyzer::headings::{
     self, Headings, HeadingsOutput, HeadingsVerbosity,
 };
-use crate::analyzer::link::{self, Link, LinkFilter, LinkGroup, LinksOutput};
+use crate::analyzer::link::{
+    self, Link, LinkElement, LinkFilter, LinkGroup, LinkOptions, LinksOutput,
+    LinksQuery,
+};
+use crate::analyzer::locale::{self, HreflangAlternate};
 use crate::analyzer::meta_tag::{
     MetaOutput, MetaTag, MetaVerbosity, extract_meta, select_meta,
 };
+use crate::analyzer::pagination::{self, Pagination};
 use crate::analyzer::text::TextOutput;
 use crate::analyzer::url_facts::UrlFacts;
 use crate::cache::CachedPage;
 use crate::client::{ClientError, FetchResult};
+use crate::normalize::TrackingParams;
 use crate::output::RenderOutput;
 
 #[derive(Debug, Clone)]
@@ -31,53 +38,76 @@ pub struct PageInfo {
     pub status: u16,
     pub title: Option<String>,
     pub lang: Option<String>,
+    /// `<base href>` resolved against the final URL; links resolve against it.
+    pub base_url: Option<String>,
     pub meta: Vec<MetaTag>,
     pub links: Vec<Link>,
     pub url_facts: UrlFacts,
+    /// Tracking parameters from the link options, reused when the URL facts
+    /// are recomputed.
+    pub tracking_params: TrackingParams,
     pub feeds: Vec<String>,
+    pub pagination: Option<Pagination>,
+    /// `<link rel="alternate" hreflang>` translations of the page.
+    pub alternates: Vec<HreflangAlternate>,
     pub structured_data: StructuredDataSummary,
+    /// `sameAs` URLs from JSON-LD, read for contact extraction.
+    pub same_as: Vec<String>,
     pub headings: Headings,
     pub text_content: Option<String>,
 }
 
+/// Maps a client error onto the analyzer variant for the same failure; a
+/// cached failure maps like the live error it recorded.
+fn client_error(e: ClientError) -> AnalyzerError {
+    match e {
+        ClientError::Fetch { url, status } => AnalyzerError::Fetch { url, status },
+        ClientError::Request { url, reason } => {
+            AnalyzerError::Parse { url, reason }
+        }
+        ClientError::InvalidUrl(msg) => AnalyzerError::InvalidUrl(msg),
+        ClientError::InvalidProxy(msg) => AnalyzerError::InvalidUrl(msg),
+        ClientError::UnknownBrowser(msg) => AnalyzerError::InvalidUrl(msg),
+        ClientError::AllAttemptsFailed { url, .. }
+        | ClientError::NotCached { url } => AnalyzerError::Fetch { url, status: 0 },
+        ClientError::CachedFailure { error, .. } => client_error(*error),
+    }
+}
+
 impl PageInfo {
     #[allow(dead_code)]
     pub async fn fetch_raw(
         url: &str,
         client: &crate::client::PageClient,
     ) -> Result<FetchResult, AnalyzerError> {
-        client.fetch(url).await.map_err(|e| match e {
-            ClientError::Fetch { url, status } => {
-                AnalyzerError::Fetch { url, status }
-            }
-            ClientError::Request { url, reason } => {
-                AnalyzerError::Parse { url, reason }
-            }
-            ClientError::InvalidUrl(msg) => AnalyzerError::InvalidUrl(msg),
-            ClientError::InvalidProxy(msg) => AnalyzerError::InvalidUrl(msg),
-            ClientError::UnknownBrowser(msg) => AnalyzerError::InvalidUrl(msg),
-            ClientError::AllAttemptsFailed { url, .. } => {
-                AnalyzerError::Fetch { url, status: 0 }
-            }
-        })
+        client.fetch(url).await.map_err(client_error)
     }
 
+    #[allow(dead_code)]
     pub fn from_fetch_result(result: &FetchResult) -> Result<Self, AnalyzerError> {
+        Self::from_fetch_result_with(result, &default_link_options())
+    }
+
+    pub fn from_fetch_result_with(
+        result: &FetchResult,
+        link_options: &LinkOptions,
+    ) -> Result<Self, AnalyzerError> {
         Self::from_raw_html(
             &result.input_url,
             &result.final_url,
             result.status,
             result.body.clone(),
+            link_options,
         )
     }
 
-    #[allow(dead_code)]
     pub fn from_cached_page(cached: &CachedPage) -> Result<Self, AnalyzerError> {
         Self::from_raw_html(
             &cached.fetch.input_url,
             &cached.fetch.final_url,
             cached.fetch.status,
             cached.html.clone(),
+            &default_link_options(),
         )
     }
 
@@ -86,6 +116,7 @@ impl PageInfo {
         final_url: &str,
         status: u16,
         body: String,
+        link_options: &LinkOptions,
     ) -> Result<Self, AnalyzerError> {
         let parsed = Url::parse(final_url)
             .map_err(|e| AnalyzerError::InvalidUrl(e.to_string()))?;
@@ -95,10 +126,16 @@ impl PageInfo {
         let title = extract_title(&document);
         let lang = extract_lang(&document);
         let meta = extract_meta(&document);
-        let links = link::extract_links(&document, &parsed);
-        let url_facts = UrlFacts::from_links(&links, &domain);
+        let base_url =
+            link::document_base(&document, &parsed).map(|base| base.to_string());
+        let links = link::extract_links_with(&document, &parsed, link_options);
+        let tracking_params = link_options.url_policy.tracking_params.clone();
+        let url_facts = UrlFacts::from_links(&links, &domain, &tracking_params);
         let feeds = detect_feeds(&links);
+        let pagination = pagination::detect_pagination(&links, &parsed);
+        let alternates = locale::hreflang_alternates(&links);
         let structured_data = detect_structured_data(&document);
+        let same_as = contacts::json_ld_same_as(&document);
         let headings = headings::extract_headings(&document);
         let text_content = dom_content_extraction::get_content(&document).ok();
         Ok(PageInfo {
@@ -108,11 +145,16 @@ impl PageInfo {
             status,
             title,
             lang,
+            base_url,
             meta,
             links,
             url_facts,
+            tracking_params,
             feeds,
+            pagination,
+            alternates,
             structured_data,
+            same_as,
             headings,
             text_content,
         })
@@ -148,21 +190,56 @@ impl PageInfo {
             lang: self.lang.clone(),
             verbosity,
             tags: self.meta_tags(verbosity),
+            alternates: self.alternates.clone(),
+        }
+    }
+
+    /// Adds `other`'s links not already present (same URL, element, and
+    /// attribute) and recomputes the URL facts. Returns how many were added.
+    pub fn merge_links(&mut self, other: &PageInfo) -> usize {
+        let before = self.links.len();
+        for link in &other.links {
+            let seen = self.links.iter().any(|existing| {
+                existing.url == link.url
+                    && existing.element == link.element
+                    && existing.attribute == link.attribute
+            });
+            if !seen {
+                self.links.push(link.clone());
+            }
         }
+        self.url_facts =
+            UrlFacts::from_links(&self.links, &self.domain, &self.tracking_params);
+        self.links.len() - before
     }
 
     pub fn links_output(&self, filter: LinkFilter) -> LinksOutput {
-        let facts = &self.url_facts;
-        let links = self
+        self.links_output_with(&LinksQuery {
+            filter,
+            ..Default::default()
+        })
+    }
+
+    pub fn links_output_with(&self, query: &LinksQuery) -> LinksOutput {
+        let links: Vec<Link> = self
             .links
             .iter()
-            .filter(|link| match filter {
-                LinkFilter::All => true,
-                LinkFilter::Internal => link.is_internal,
-                LinkFilter::External => !link.is_internal,
-            })
+            .filter(|link| query.matches(link))
             .cloned()
             .collect();
+        // Summaries describe the listed links, so filters apply to them too.
+        let filtered_facts;
+        let facts = if links.len() == self.links.len() {
+            &self.url_facts
+        } else {
+            filtered_facts =
+                UrlFacts::from_links(&links, &self.domain, &self.tracking_params);
+            &filtered_facts
+        };
+        let selector_groups = link::selector_groups(&links);
+        let rel_counts = link::rel_counts(&links);
+        let attribute_counts = link::attribute_counts(&links);
+        let occurrences = query.dedupe.then(|| link::dedupe_links(&links));
         let groups = facts
             .top_first_segments
             .iter()
@@ -176,16 +253,43 @@ impl PageInfo {
                     section: section.clone(),
                     count: *count,
                     samples,
+                    anchors: facts
+                        .anchor_stats_by_section
+                        .get(section)
+                        .cloned()
+                        .unwrap_or_default(),
                 }
             })
             .collect();
         LinksOutput {
             url: self.final_url.clone(),
-            filter,
+            base: self.base_url.clone(),
+            filter: query.filter,
+            elements: query.elements.clone(),
+            regions: query.regions.clone(),
             total_internal: facts.total_internal,
             total_external: facts.total_external,
+            total_same_host: facts.total_same_host,
+            total_subdomain: facts.total_subdomain,
+            host_counts: facts.host_counts.clone(),
+            top_external_domains: facts.top_external_domains.clone(),
+            region_counts: facts
+                .region_counts
+                .iter()
+                .map(|(region, count)| (*region, *count))
+                .collect(),
+            locales: facts.locales.clone(),
+            alternates: self.alternates.clone(),
+            rel_counts,
+            attribute_counts,
             links,
+            occurrences,
             groups,
+            selector_groups,
+            url_templates: facts.url_templates.clone(),
+            pagination: self.pagination.clone(),
+            followed: Vec::new(),
+            query_params: facts.query_params.clone(),
             depth_distribution: facts
                 .depth_distribution
                 .iter()
@@ -330,6 +434,13 @@ impl PageInfo {
         }
     }
 
+    pub fn contacts_output(&self) -> ContactsOutput {
+        ContactsOutput {
+            url: self.final_url.clone(),
+            contacts: contacts::extract_contacts(&self.links, &self.same_as),
+        }
+    }
+
     pub fn text_output(&self) -> TextOutput {
         TextOutput {
             url: self.final_url.clone(),
@@ -341,6 +452,14 @@ impl PageInfo {
     }
 }
 
+/// Options behind `extract_links`: normalized links, no extra rules.
+fn default_link_options() -> LinkOptions {
+    LinkOptions {
+        normalize: true,
+        ..Default::default()
+    }
+}
+
 fn extract_title(document: &Html) -> Option<String> {
     let selector = Selector::parse("title").ok()?;
     document
@@ -359,7 +478,10 @@ fn extract_lang(document: &Html) -> Option<String> {
 
 fn detect_feeds(links: &[link::Link]) -> Vec<String> {
     let mut feeds = std::collections::BTreeSet::new();
-    for link in links {
+    for link in links
+        .iter()
+        .filter(|link| matches!(link.element, LinkElement::A | LinkElement::Link))
+    {
         let lower = link.url.as_str().to_ascii_lowercase();
         if lower.contains("/rss")
             || lower.contains("rss/")
@@ -421,7 +543,8 @@ fn detect_structured_data(document: &Html) -> StructuredDataSummary {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::cache::{CachedFetch, CachedPage};
+    use crate::analyzer::region::LinkRegion;
+    use crate::cache::{CachedFailure, CachedFetch, CachedPage, FailureKind};
 
     const FAKE_HTML: &str = r##"<!DOCTYPE html>
 <html lang="en">
@@ -467,6 +590,7 @@ mod tests {
                 normalized_final_url: "example.com/".to_string(),
                 status: 200,
                 fetched_at: "0".to_string(),
+                ..Default::default()
             },
             headers: std::collections::HashMap::new(),
             html: FAKE_HTML.to_string(),
@@ -485,6 +609,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn cached_failures_map_like_live_errors() {
+        let failure = |kind, status, reason: Option<&str>| {
+            ClientError::from_cached_failure(CachedFailure {
+                url: "https://example.com/gone".to_string(),
+                kind,
+                status,
+                reason: reason.map(str::to_string),
+                attempts: 1,
+                failed_at: "0".to_string(),
+            })
+        };
+
+        let err = client_error(failure(FailureKind::Http, Some(404), None));
+        assert!(matches!(err, AnalyzerError::Fetch { status: 404, .. }));
+        let live = client_error(ClientError::Fetch {
+            url: "https://example.com/gone".to_string(),
+            status: 404,
+        });
+        assert_eq!(err.to_string(), live.to_string());
+
+        let err = client_error(failure(
+            FailureKind::Request,
+            None,
+            Some("connection refused"),
+        ));
+        assert!(matches!(err, AnalyzerError::Parse { .. }));
+        assert!(err.to_string().contains("connection refused"));
+    }
+
     #[test]
     fn from_cached_page_extracts_title() {
         let page = PageInfo::from_cached_page(&fake_cached_page()).unwrap();
@@ -674,6 +828,7 @@ mod tests {
                 normalized_final_url: "example.com/".to_string(),
                 status: 200,
                 fetched_at: "0".to_string(),
+                ..Default::default()
             },
             headers: std::collections::HashMap::new(),
             html: "<html><body></body></html>".to_string(),
@@ -686,6 +841,132 @@ mod tests {
         assert!(page.feeds.is_empty());
     }
 
+    #[test]
+    fn base_href_is_reported_and_used_for_links() {
+        let mut cp = fake_cached_page();
+        cp.html = r#"<html><head><base href="/blog/"></head>
+            <body><a href="post-1">Post</a></body></html>"#
+            .to_string();
+        let page = PageInfo::from_cached_page(&cp).unwrap();
+        assert_eq!(page.base_url.as_deref(), Some("https://example.com/blog/"));
+        assert_eq!(
+            page.links[0].url.as_str(),
+            "https://example.com/blog/post-1"
+        );
+
+        let output = page.links_output(LinkFilter::All);
+        assert_eq!(output.base.as_deref(), Some("https://example.com/blog/"));
+        assert!(
+            output
+                .render_text()
+                .contains("Base: https://example.com/blog/")
+        );
+        assert!(
+            PageInfo::from_cached_page(&fake_cached_page())
+                .unwrap()
+                .base_url
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn hreflang_alternates_and_locales_reach_links_and_meta() {
+        let mut cached = fake_cached_page();
+        cached.html = r#"<head><meta name="description" content="D">
+            <link rel="alternate" hreflang="de" href="/de/">
+            <link rel="alternate" hreflang="x-default" href="/"></head>
+            <a href="/de/news/a">A</a><a href="/en/news/b">B</a>"#
+            .to_string();
+        let page = PageInfo::from_cached_page(&cached).unwrap();
+        assert_eq!(page.alternates.len(), 2);
+
+        let links = page.links_output(LinkFilter::All);
+        assert_eq!(
+            links.locales,
+            [("de".to_string(), 1), ("en".to_string(), 1)]
+        );
+        assert_eq!(links.groups[0].section, "news");
+        assert!(links.render_text().contains("Locales: de (1), en (1)"));
+        assert!(links.render_text().contains("## Hreflang Alternates"));
+
+        let meta = page.meta_output(MetaVerbosity::Main);
+        assert_eq!(meta.alternates[0].hreflang, "de");
+        assert!(
+            meta.render_text()
+                .contains("x-default\thttps://example.com/")
+        );
+        assert!(meta.render_json().contains("\"alternates\""));
+    }
+
+    #[test]
+    fn contacts_output_combines_links_and_json_ld() {
+        let mut cached = fake_cached_page();
+        cached.html = r#"<head><script type="application/ld+json">
+            {"@type": "Organization", "sameAs": "https://t.me/example"}
+            </script></head>
+            <a href="mailto:hi@example.com">Mail</a>
+            <a href="https://twitter.com/example">X</a>"#
+            .to_string();
+        let page = PageInfo::from_cached_page(&cached).unwrap();
+        let output = page.contacts_output();
+        assert_eq!(output.contacts.emails, ["hi@example.com"]);
+        let networks: Vec<_> = output
+            .contacts
+            .socials
+            .iter()
+            .map(|profile| profile.network.as_str())
+            .collect();
+        assert_eq!(networks, ["x", "telegram"]);
+        assert!(output.render_toon().contains("telegram"));
+    }
+
+    #[test]
+    fn filtered_links_output_summarizes_only_listed_links() {
+        let mut cached = fake_cached_page();
+        cached.html = r#"<nav><a href="/docs/a">A</a></nav>
+            <footer><a href="/about">About</a>
+            <a href="https://other.org/x">X</a></footer>"#
+            .to_string();
+        let page = PageInfo::from_cached_page(&cached).unwrap();
+
+        let external = page.links_output(LinkFilter::External);
+        assert_eq!((external.total_internal, external.total_external), (0, 1));
+        assert!(external.groups.is_empty());
+
+        let nav = page.links_output_with(&LinksQuery {
+            regions: vec![LinkRegion::Nav],
+            ..Default::default()
+        });
+        assert_eq!(nav.region_counts, [(LinkRegion::Nav, 1)]);
+        assert_eq!(nav.groups[0].section, "docs");
+        assert_eq!(nav.total_external, 0);
+
+        let all = page.links_output(LinkFilter::All);
+        assert_eq!((all.total_internal, all.total_external), (2, 1));
+    }
+
+    #[test]
+    fn merge_links_adds_unseen_links_and_recomputes_facts() {
+        let mut first = fake_cached_page();
+        first.html =
+            r#"<a href="/news/a">A</a><a rel="next" href="/?page=2">Next</a>"#
+                .to_string();
+        let mut page = PageInfo::from_cached_page(&first).unwrap();
+        assert_eq!(
+            page.pagination.as_ref().and_then(|p| p.next.as_deref()),
+            Some("https://example.com/?page=2")
+        );
+
+        let mut second = fake_cached_page();
+        second.html =
+            r#"<a href="/news/a">A</a><a href="/news/b">B</a>"#.to_string();
+        let other = PageInfo::from_cached_page(&second).unwrap();
+
+        assert_eq!(page.merge_links(&other), 1);
+        assert_eq!(page.links.len(), 3);
+        assert_eq!(page.url_facts.total_internal, 3);
+    }
+
     #[test]
     fn from_fetch_result_extracts_title() {
         let result = fake_fetch_result();
@@ -0,0 +1,324 @@
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::analyzer::link::Link;
+use crate::analyzer::query_params::ParamRole;
+use crate::analyzer::rel::RelToken;
+use crate::analyzer::url_template::is_digits;
+
+const NEXT_TEXTS: &[&str] = &["next", "next page", "older", "older posts"];
+const PREV_TEXTS: &[&str] =
+    &["prev", "previous", "previous page", "newer", "newer posts"];
+/// Path segments that precede a page number: `/page/2/`, `/p/3`.
+const PAGE_SEGMENTS: &[&str] = &["page", "p", "pages"];
+/// Pagination params that hold a page size, not a page number.
+const PAGE_SIZE_PARAMS: &[&str] =
+    &["limit", "per_page", "perpage", "page_size", "pagesize"];
+
+/// How a listing links to its next page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaginationScheme {
+    /// `<link rel="next">` or `<a rel="next">`.
+    RelNext,
+    /// `?page=N` style query parameter.
+    QueryParam,
+    /// `/page/N/` style path segment.
+    PathSegment,
+    /// An anchor labelled "Next", "›", "Older posts", ...
+    AnchorText,
+}
+
+impl PaginationScheme {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::RelNext => "rel_next",
+            Self::QueryParam => "query_param",
+            Self::PathSegment => "path_segment",
+            Self::AnchorText => "anchor_text",
+        }
+    }
+}
+
+/// One page visited by `links --follow-next`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FollowedPage {
+    pub url: String,
+    pub links: usize,
+    /// Links not seen on any earlier page.
+    pub new_links: usize,
+    /// Why the page could not be loaded; the walk stops there.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Pagination {
+    pub scheme: PaginationScheme,
+    pub next: Option<String>,
+    pub prev: Option<String>,
+    /// Query parameter carrying the page number (`page`), or the path pattern
+    /// (`/page/{n}`).
+    pub param: Option<String>,
+}
+
+/// Detects pagination from a page's links. Signals are tried in order of
+/// reliability: `rel` tokens, page-number query parameters, `/page/N` paths,
+/// then anchor text.
+pub fn detect_pagination(links: &[Link], page_url: &Url) -> Option<Pagination> {
+    let rel = |token| links.iter().find(|link| link.has_rel(token));
+    let (next, prev, scheme) = match (rel(RelToken::Next), rel(RelToken::Prev)) {
+        (None, None) => numbered_neighbours(links, page_url)
+            .or_else(|| labelled_neighbours(links))?,
+        (next, prev) => (next, prev, PaginationScheme::RelNext),
+    };
+
+    let param = next
+        .or(prev)
+        .and_then(|link| page_param(page_url, &link.url));
+    Some(Pagination {
+        scheme,
+        next: next.map(|link| link.url.to_string()),
+        prev: prev.map(|link| link.url.to_string()),
+        param,
+    })
+}
+
+type Neighbours<'a> = (Option<&'a Link>, Option<&'a Link>, PaginationScheme);
+
+/// Links to page `current ± 1` through a page-number query parameter or a
+/// `/page/N` path on the same path as `page_url`.
+fn numbered_neighbours<'a>(
+    links: &'a [Link],
+    page_url: &Url,
+) -> Option<Neighbours<'a>> {
+    let current = page_number(page_url, page_url)
+        .map(|(_, number)| number)
+        .or_else(|| split_page_path(page_url.path()).map(|(_, number)| number))
+        .unwrap_or(1);
+    let mut scheme = None;
+    let mut find = |target: u64| {
+        links
+            .iter()
+            .filter(|link| link.is_internal)
+            .find(|link| match page_number(page_url, &link.url) {
+                Some((found, number)) if number == target => {
+                    scheme.get_or_insert(found);
+                    true
+                }
+                _ => false,
+            })
+    };
+    let next = current.checked_add(1).and_then(&mut find);
+    let prev = current
+        .checked_sub(1)
+        .filter(|n| *n > 0)
+        .and_then(&mut find);
+    Some((next, prev, scheme?))
+}
+
+/// Links whose text reads "Next"/"Previous" or an arrow.
+fn labelled_neighbours(links: &[Link]) -> Option<Neighbours<'_>> {
+    let labelled = |texts: &[&str], arrows: &[&str]| {
+        links
+            .iter()
+            .filter(|link| link.element.is_navigational())
+            .find(|link| {
+                let Some(text) = link.text.as_deref() else {
+                    return false;
+                };
+                let words: String = text
+                    .chars()
+                    .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+                    .collect::<String>()
+                    .to_lowercase();
+                let words = words.split_whitespace().collect::<Vec<_>>().join(" ");
+                if words.is_empty() {
+                    arrows.contains(&text.trim())
+                } else {
+                    texts.contains(&words.as_str())
+                }
+            })
+    };
+    let next = labelled(NEXT_TEXTS, &["›", "»", "→", ">", ">>"]);
+    let prev = labelled(PREV_TEXTS, &["‹", "«", "←", "<", "<<"]);
+    (next.is_some() || prev.is_some()).then_some((
+        next,
+        prev,
+        PaginationScheme::AnchorText,
+    ))
+}
+
+/// Page number of `url` when it is `page_url` with a page-number query
+/// parameter or `/page/N` path; the plain listing URL is not numbered.
+fn page_number(page_url: &Url, url: &Url) -> Option<(PaginationScheme, u64)> {
+    if url.host_str() != page_url.host_str() {
+        return None;
+    }
+    if url.path() == page_url.path() {
+        return url
+            .query_pairs()
+            .find(|(name, value)| {
+                ParamRole::of(name) == Some(ParamRole::Pagination)
+                    && !PAGE_SIZE_PARAMS
+                        .contains(&name.to_ascii_lowercase().as_str())
+                    && is_digits(value)
+            })
+            .and_then(|(_, value)| value.parse().ok())
+            .map(|number| (PaginationScheme::QueryParam, number));
+    }
+    let (listing, number) = split_page_path(url.path())?;
+    (listing
+        == split_page_path(page_url.path()).map_or(page_url.path(), |(l, _)| l)
+        || listing.trim_end_matches('/') == page_url.path().trim_end_matches('/'))
+    .then_some((PaginationScheme::PathSegment, number))
+}
+
+/// `/blog/page/3/` -> (`/blog`, 3)
+fn split_page_path(path: &str) -> Option<(&str, u64)> {
+    let trimmed = path.trim_end_matches('/');
+    let (rest, number) = trimmed.rsplit_once('/')?;
+    let (listing, marker) = rest.rsplit_once('/')?;
+    if !PAGE_SEGMENTS.contains(&marker) || !is_digits(number) {
+        return None;
+    }
+    Some((listing, number.parse().ok()?))
+}
+
+/// Which parameter changes between `from` and `to`: a query name, or
+/// `/page/{n}` for path pagination.
+fn page_param(from: &Url, to: &Url) -> Option<String> {
+    if let Some((listing, _)) = split_page_path(to.path()) {
+        let marker = to.path()[listing.len()..]
+            .trim_start_matches('/')
+            .split('/')
+            .next()
+            .unwrap_or("page");
+        return Some(format!("/{marker}/{{n}}"));
+    }
+    let before: Vec<(String, String)> = from.query_pairs().into_owned().collect();
+    to.query_pairs()
+        .filter(|(_, value)| is_digits(value))
+        .find(|(name, value)| {
+            !before
+                .iter()
+                .any(|(b_name, b_value)| b_name == name && b_value == value)
+        })
+        .map(|(name, _)| name.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::link::extract_links;
+    use dom_content_extraction::scraper::Html;
+
+    fn detect(page: &str, body: &str) -> Option<Pagination> {
+        let page = Url::parse(page).unwrap();
+        let doc = Html::parse_document(body);
+        detect_pagination(&extract_links(&doc, &page), &page)
+    }
+
+    #[test]
+    fn prefers_rel_next_and_infers_query_param() {
+        let result = detect(
+            "https://example.com/list?page=2",
+            r#"<link rel="prev" href="/list?page=1">
+               <link rel="next" href="/list?page=3">"#,
+        )
+        .unwrap();
+        assert_eq!(result.scheme, PaginationScheme::RelNext);
+        assert_eq!(
+            result.next.as_deref(),
+            Some("https://example.com/list?page=3")
+        );
+        assert_eq!(
+            result.prev.as_deref(),
+            Some("https://example.com/list?page=1")
+        );
+        assert_eq!(result.param.as_deref(), Some("page"));
+    }
+
+    #[test]
+    fn detects_numbered_query_links() {
+        let result = detect(
+            "https://example.com/shop?sort=price",
+            r#"<a href="/shop?sort=price&p=2">2</a><a href="/shop?sort=price&p=3">3</a>"#,
+        )
+        .unwrap();
+        assert_eq!(result.scheme, PaginationScheme::QueryParam);
+        assert_eq!(
+            result.next.as_deref(),
+            Some("https://example.com/shop?sort=price&p=2")
+        );
+        assert!(result.prev.is_none());
+        assert_eq!(result.param.as_deref(), Some("p"));
+    }
+
+    #[test]
+    fn last_representable_page_number_has_no_next() {
+        let result = detect(
+            "https://example.com/list?page=18446744073709551615",
+            r#"<a href="/list?page=18446744073709551614">Back</a>"#,
+        )
+        .unwrap();
+        assert!(result.next.is_none());
+        assert_eq!(
+            result.prev.as_deref(),
+            Some("https://example.com/list?page=18446744073709551614")
+        );
+    }
+
+    #[test]
+    fn ignores_page_size_params() {
+        let result = detect(
+            "https://example.com/list?limit=20&page=1",
+            r#"<a href="/list?limit=21">More</a><a href="/list?limit=20&page=2">2</a>"#,
+        )
+        .unwrap();
+        assert_eq!(
+            result.next.as_deref(),
+            Some("https://example.com/list?limit=20&page=2")
+        );
+    }
+
+    #[test]
+    fn detects_page_path_segments() {
+        let result = detect(
+            "https://example.com/blog/page/2/",
+            r#"<a href="/blog/">1</a><a href="/blog/page/3/">3</a>
+               <a href="/blog/page/1/">1</a>"#,
+        )
+        .unwrap();
+        assert_eq!(result.scheme, PaginationScheme::PathSegment);
+        assert_eq!(
+            result.next.as_deref(),
+            Some("https://example.com/blog/page/3/")
+        );
+        assert_eq!(
+            result.prev.as_deref(),
+            Some("https://example.com/blog/page/1/")
+        );
+        assert_eq!(result.param.as_deref(), Some("/page/{n}"));
+    }
+
+    #[test]
+    fn falls_back_to_anchor_text() {
+        let result = detect(
+            "https://example.com/archive",
+            r#"<a href="/archive?before=abc">Older posts ›</a><a href="/x">Next-gen</a>"#,
+        )
+        .unwrap();
+        assert_eq!(result.scheme, PaginationScheme::AnchorText);
+        assert_eq!(
+            result.next.as_deref(),
+            Some("https://example.com/archive?before=abc")
+        );
+        assert!(result.param.is_none());
+
+        assert!(
+            detect("https://example.com/", r#"<a href="/about">About</a>"#)
+                .is_none()
+        );
+    }
+}
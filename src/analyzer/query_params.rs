@@ -0,0 +1,311 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::analyzer::url_template::{is_digits, is_hash, is_uuid};
+use crate::normalize::TrackingParams;
+
+const MAX_PARAMS: usize = 30;
+const MAX_VALUE_SAMPLES: usize = 5;
+/// More distinct values than this reads as free text rather than an enum.
+const MAX_ENUM_VALUES: usize = 8;
+/// Values longer than this are free text even when there are few of them.
+const MAX_ENUM_VALUE_LEN: usize = 24;
+
+const PAGINATION_PARAMS: &[&str] = &[
+    "page",
+    "p",
+    "pg",
+    "paged",
+    "pagenum",
+    "page_num",
+    "offset",
+    "start",
+    "cursor",
+    "after",
+    "before",
+    "per_page",
+    "perpage",
+    "page_size",
+    "pagesize",
+    "limit",
+];
+const SORT_PARAMS: &[&str] = &[
+    "sort",
+    "sortby",
+    "sort_by",
+    "order",
+    "orderby",
+    "order_by",
+    "dir",
+    "direction",
+    "sort_order",
+];
+const FILTER_PARAMS: &[&str] = &[
+    "filter",
+    "q",
+    "query",
+    "search",
+    "s",
+    "category",
+    "cat",
+    "tag",
+    "type",
+    "brand",
+    "color",
+    "size",
+    "price",
+    "min_price",
+    "max_price",
+];
+const SESSION_PARAMS: &[&str] = &[
+    "sid",
+    "session",
+    "sessionid",
+    "session_id",
+    "phpsessid",
+    "jsessionid",
+    "aspsessionid",
+    "token",
+];
+
+/// Inferred type of a parameter's values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParamValueType {
+    Int,
+    /// A small set of short values (`asc`, `desc`).
+    Enum,
+    /// Opaque identifiers: uuids, hashes, long mixed tokens.
+    Id,
+    Text,
+}
+
+impl ParamValueType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Int => "int",
+            Self::Enum => "enum",
+            Self::Id => "id",
+            Self::Text => "text",
+        }
+    }
+}
+
+/// What a parameter likely does, guessed from its name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParamRole {
+    Pagination,
+    Sort,
+    Filter,
+    Session,
+    Tracking,
+}
+
+impl ParamRole {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pagination => "pagination",
+            Self::Sort => "sort",
+            Self::Filter => "filter",
+            Self::Session => "session",
+            Self::Tracking => "tracking",
+        }
+    }
+
+    /// Role of `name`, treating the built-in tracking list as tracking.
+    pub fn of(name: &str) -> Option<Self> {
+        Self::of_with(name, &TrackingParams::default())
+    }
+
+    /// Role of `name`, treating anything matched by `tracking` as tracking.
+    pub fn of_with(name: &str, tracking: &TrackingParams) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+        let name = name.as_str();
+        if tracking.matches(name) {
+            Some(Self::Tracking)
+        } else if SESSION_PARAMS.contains(&name) {
+            Some(Self::Session)
+        } else if PAGINATION_PARAMS.contains(&name) {
+            Some(Self::Pagination)
+        } else if SORT_PARAMS.contains(&name) {
+            Some(Self::Sort)
+        } else if FILTER_PARAMS.contains(&name)
+            || name.starts_with("filter")
+            || name.contains('[')
+        {
+            Some(Self::Filter)
+        } else {
+            None
+        }
+    }
+}
+
+/// One query parameter seen across a set of URLs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueryParam {
+    pub name: String,
+    /// Distinct URLs carrying the parameter.
+    pub count: usize,
+    pub distinct_values: usize,
+    pub samples: Vec<String>,
+    pub value_type: ParamValueType,
+    pub role: Option<ParamRole>,
+}
+
+/// Query parameters across `urls`, most frequent first. Each distinct URL is
+/// counted once; names matched by `tracking` get the tracking role.
+pub fn analyze_query_params<'a>(
+    urls: impl IntoIterator<Item = &'a Url>,
+    tracking: &TrackingParams,
+) -> Vec<QueryParam> {
+    let urls: BTreeSet<&Url> = urls.into_iter().collect();
+    let mut seen: BTreeMap<String, (usize, BTreeSet<String>)> = BTreeMap::new();
+    for url in urls {
+        let mut names: BTreeSet<String> = BTreeSet::new();
+        for (name, value) in url.query_pairs() {
+            let entry = seen.entry(name.to_string()).or_default();
+            entry.1.insert(value.into_owned());
+            names.insert(name.into_owned());
+        }
+        for name in names {
+            if let Some(entry) = seen.get_mut(&name) {
+                entry.0 += 1;
+            }
+        }
+    }
+
+    let mut params: Vec<QueryParam> = seen
+        .into_iter()
+        .map(|(name, (count, values))| QueryParam {
+            role: ParamRole::of_with(&name, tracking),
+            value_type: value_type(&values),
+            distinct_values: values.len(),
+            samples: values.into_iter().take(MAX_VALUE_SAMPLES).collect(),
+            count,
+            name,
+        })
+        .collect();
+    params.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+    params.truncate(MAX_PARAMS);
+    params
+}
+
+fn value_type(values: &BTreeSet<String>) -> ParamValueType {
+    let values: Vec<&str> = values
+        .iter()
+        .map(String::as_str)
+        .filter(|value| !value.is_empty())
+        .collect();
+    if values.is_empty() {
+        return ParamValueType::Enum;
+    }
+    if values.iter().all(|value| is_digits(value)) {
+        ParamValueType::Int
+    } else if values.iter().all(|value| is_id(value)) {
+        ParamValueType::Id
+    } else if values.len() <= MAX_ENUM_VALUES
+        && values.iter().all(|value| {
+            value.len() <= MAX_ENUM_VALUE_LEN
+                && !value.contains(char::is_whitespace)
+        })
+    {
+        ParamValueType::Enum
+    } else {
+        ParamValueType::Text
+    }
+}
+
+/// Uuids, hex hashes, and long tokens mixing letters and digits.
+fn is_id(value: &str) -> bool {
+    is_uuid(value)
+        || is_hash(value)
+        || (value.len() >= 16
+            && value.bytes().all(|b| b.is_ascii_alphanumeric())
+            && value.bytes().any(|b| b.is_ascii_digit())
+            && value.bytes().any(|b| b.is_ascii_alphabetic()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(urls: &[&str]) -> Vec<QueryParam> {
+        let urls: Vec<Url> =
+            urls.iter().map(|url| Url::parse(url).unwrap()).collect();
+        analyze_query_params(&urls, &TrackingParams::default())
+    }
+
+    fn param<'a>(params: &'a [QueryParam], name: &str) -> &'a QueryParam {
+        params.iter().find(|param| param.name == name).unwrap()
+    }
+
+    #[test]
+    fn counts_urls_and_infers_value_types() {
+        let result = params(&[
+            "https://example.com/list?page=2&sort=price&q=red+shoes",
+            "https://example.com/list?page=3&sort=date&q=blue",
+            "https://example.com/list?page=3&sort=date&q=blue",
+            "https://example.com/item?id=550e8400-e29b-41d4-a716-446655440000",
+        ]);
+
+        let page = param(&result, "page");
+        assert_eq!(page.count, 2);
+        assert_eq!(page.value_type, ParamValueType::Int);
+        assert_eq!(page.role, Some(ParamRole::Pagination));
+        assert_eq!(page.samples, ["2", "3"]);
+
+        let sort = param(&result, "sort");
+        assert_eq!(sort.value_type, ParamValueType::Enum);
+        assert_eq!(sort.role, Some(ParamRole::Sort));
+
+        let query = param(&result, "q");
+        assert_eq!(query.value_type, ParamValueType::Text);
+        assert_eq!(query.role, Some(ParamRole::Filter));
+
+        let id = param(&result, "id");
+        assert_eq!(id.count, 1);
+        assert_eq!(id.value_type, ParamValueType::Id);
+        assert_eq!(id.role, None);
+        assert_eq!(result[0].name, "page");
+    }
+
+    #[test]
+    fn flags_session_tracking_and_filter_params() {
+        assert_eq!(ParamRole::of("PHPSESSID"), Some(ParamRole::Session));
+        assert_eq!(ParamRole::of("utm_campaign"), Some(ParamRole::Tracking));
+        assert_eq!(ParamRole::of("fbclid"), Some(ParamRole::Tracking));
+        assert_eq!(ParamRole::of("filter[color]"), Some(ParamRole::Filter));
+        assert_eq!(ParamRole::of("per_page"), Some(ParamRole::Pagination));
+        assert_eq!(ParamRole::of("lang"), None);
+    }
+
+    #[test]
+    fn configured_tracking_params_get_the_tracking_role() {
+        let tracking = TrackingParams::with_extra(&["pk_*"]);
+        assert_eq!(ParamRole::of("pk_campaign"), None);
+        assert_eq!(
+            ParamRole::of_with("pk_campaign", &tracking),
+            Some(ParamRole::Tracking)
+        );
+
+        let urls = [Url::parse("https://example.com/?pk_kwd=x&page=2").unwrap()];
+        let result = analyze_query_params(&urls, &tracking);
+        assert_eq!(param(&result, "pk_kwd").role, Some(ParamRole::Tracking));
+        assert_eq!(param(&result, "page").role, Some(ParamRole::Pagination));
+    }
+
+    #[test]
+    fn many_distinct_words_are_text() {
+        let urls: Vec<String> = (0..10)
+            .map(|i| format!("https://example.com/s?term=word{}x", "a".repeat(i)))
+            .collect();
+        let urls: Vec<&str> = urls.iter().map(String::as_str).collect();
+        let result = params(&urls);
+        assert_eq!(result[0].distinct_values, 10);
+        assert_eq!(result[0].samples.len(), MAX_VALUE_SAMPLES);
+        assert_eq!(result[0].value_type, ParamValueType::Text);
+    }
+}
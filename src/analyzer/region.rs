@@ -0,0 +1,198 @@
+use dom_content_extraction::scraper::ElementRef;
+use serde::{Deserialize, Serialize};
+
+/// Page landmark a link sits in: the nearest enclosing landmark element,
+/// ARIA role, or class/id heuristic.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkRegion {
+    Nav,
+    Header,
+    Footer,
+    Aside,
+    Main,
+    Article,
+    /// `class`/`id` containing `breadcrumb`.
+    Breadcrumb,
+    /// No enclosing landmark.
+    #[default]
+    Other,
+}
+
+impl LinkRegion {
+    pub const ALL: [LinkRegion; 8] = [
+        LinkRegion::Nav,
+        LinkRegion::Header,
+        LinkRegion::Footer,
+        LinkRegion::Aside,
+        LinkRegion::Main,
+        LinkRegion::Article,
+        LinkRegion::Breadcrumb,
+        LinkRegion::Other,
+    ];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|region| region.as_str() == value)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Nav => "nav",
+            Self::Header => "header",
+            Self::Footer => "footer",
+            Self::Aside => "aside",
+            Self::Main => "main",
+            Self::Article => "article",
+            Self::Breadcrumb => "breadcrumb",
+            Self::Other => "other",
+        }
+    }
+
+    /// Whether a filter on `self` keeps links whose region is `region`.
+    /// `main` also keeps `article`: the nearest landmark of a link in
+    /// `<main><article>` is the article, and that is the page content.
+    pub fn includes(self, region: LinkRegion) -> bool {
+        self == region || self == Self::Main && region == Self::Article
+    }
+}
+
+/// Region of the nearest landmark enclosing `element` (the element itself
+/// included).
+pub fn link_region(element: ElementRef<'_>) -> LinkRegion {
+    std::iter::once(element)
+        .chain(element.ancestors().filter_map(ElementRef::wrap))
+        .find_map(landmark)
+        .unwrap_or_default()
+}
+
+fn landmark(element: ElementRef<'_>) -> Option<LinkRegion> {
+    let value = element.value();
+    let by_tag = match value.name() {
+        "nav" => Some(LinkRegion::Nav),
+        "header" => Some(LinkRegion::Header),
+        "footer" => Some(LinkRegion::Footer),
+        "aside" => Some(LinkRegion::Aside),
+        "main" => Some(LinkRegion::Main),
+        "article" => Some(LinkRegion::Article),
+        _ => None,
+    };
+    let by_role = || match value.attr("role")?.trim() {
+        "navigation" | "menu" | "menubar" => Some(LinkRegion::Nav),
+        "banner" => Some(LinkRegion::Header),
+        "contentinfo" => Some(LinkRegion::Footer),
+        "complementary" => Some(LinkRegion::Aside),
+        "main" => Some(LinkRegion::Main),
+        "article" => Some(LinkRegion::Article),
+        _ => None,
+    };
+    let by_class = || {
+        let names = value.attr("class").into_iter().chain(value.attr("id"));
+        let words: Vec<String> = names
+            .flat_map(|names| names.split(|c: char| !c.is_ascii_alphanumeric()))
+            .map(str::to_ascii_lowercase)
+            .collect();
+        let has = |keys: &[&str]| words.iter().any(|w| keys.contains(&w.as_str()));
+        if has(&["breadcrumb", "breadcrumbs"]) {
+            Some(LinkRegion::Breadcrumb)
+        } else if has(&["nav", "navbar", "navigation", "menu"]) {
+            Some(LinkRegion::Nav)
+        } else if has(&["sidebar"]) {
+            Some(LinkRegion::Aside)
+        } else if has(&["footer"]) {
+            Some(LinkRegion::Footer)
+        } else {
+            None
+        }
+    };
+    by_tag.or_else(by_role).or_else(by_class)
+}
+
+#[cfg(test)]
+mod tests {
+    use dom_content_extraction::scraper::{Html, Selector};
+
+    use super::*;
+
+    fn regions(html: &str) -> Vec<LinkRegion> {
+        let doc = Html::parse_document(html);
+        let selector = Selector::parse("a").unwrap();
+        doc.select(&selector).map(link_region).collect()
+    }
+
+    #[test]
+    fn detects_landmark_elements() {
+        let html = r#"<header><a href="/">Home</a></header>
+            <nav><a href="/a">A</a></nav>
+            <main><article><a href="/p">Post</a></article><a href="/m">M</a></main>
+            <aside><a href="/s">S</a></aside>
+            <footer><a href="/f">F</a></footer>
+            <a href="/loose">Loose</a>"#;
+        assert_eq!(
+            regions(html),
+            vec![
+                LinkRegion::Header,
+                LinkRegion::Nav,
+                LinkRegion::Article,
+                LinkRegion::Main,
+                LinkRegion::Aside,
+                LinkRegion::Footer,
+                LinkRegion::Other,
+            ]
+        );
+    }
+
+    #[test]
+    fn nearest_landmark_wins() {
+        let html = r#"<main><nav><a href="/x">X</a></nav></main>
+            <header><div class="breadcrumbs"><a href="/">Home</a></div></header>"#;
+        assert_eq!(regions(html), vec![LinkRegion::Nav, LinkRegion::Breadcrumb]);
+    }
+
+    #[test]
+    fn detects_aria_roles_and_class_heuristics() {
+        let html = r#"<div role="navigation"><a href="/a">A</a></div>
+            <div role="contentinfo"><a href="/b">B</a></div>
+            <ul class="main-menu"><li><a href="/c">C</a></li></ul>
+            <div id="site_footer"><a href="/d">D</a></div>
+            <div class="navigator"><a href="/e">E</a></div>"#;
+        assert_eq!(
+            regions(html),
+            vec![
+                LinkRegion::Nav,
+                LinkRegion::Footer,
+                LinkRegion::Nav,
+                LinkRegion::Footer,
+                LinkRegion::Other,
+            ]
+        );
+    }
+
+    #[test]
+    fn main_includes_article_links() {
+        assert!(LinkRegion::Main.includes(LinkRegion::Main));
+        assert!(LinkRegion::Main.includes(LinkRegion::Article));
+        assert!(!LinkRegion::Article.includes(LinkRegion::Main));
+        assert!(!LinkRegion::Main.includes(LinkRegion::Aside));
+    }
+
+    #[test]
+    fn regions_round_trip_through_names() {
+        for region in LinkRegion::ALL {
+            assert_eq!(LinkRegion::parse(region.as_str()), Some(region));
+        }
+    }
+}
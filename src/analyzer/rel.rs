@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+
+/// A `rel` token that changes how a crawler should treat a link.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum RelToken {
+    Nofollow,
+    Sponsored,
+    Ugc,
+    Noopener,
+    Alternate,
+    Next,
+    /// `prev` or `previous`.
+    Prev,
+    Canonical,
+}
+
+impl RelToken {
+    pub const ALL: [RelToken; 8] = [
+        RelToken::Nofollow,
+        RelToken::Sponsored,
+        RelToken::Ugc,
+        RelToken::Noopener,
+        RelToken::Alternate,
+        RelToken::Next,
+        RelToken::Prev,
+        RelToken::Canonical,
+    ];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.to_ascii_lowercase();
+        if value == "previous" {
+            return Some(Self::Prev);
+        }
+        Self::ALL.into_iter().find(|token| token.as_str() == value)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Nofollow => "nofollow",
+            Self::Sponsored => "sponsored",
+            Self::Ugc => "ugc",
+            Self::Noopener => "noopener",
+            Self::Alternate => "alternate",
+            Self::Next => "next",
+            Self::Prev => "prev",
+            Self::Canonical => "canonical",
+        }
+    }
+}
+
+/// Known tokens of a `rel` attribute in order of appearance, without
+/// duplicates. Unknown tokens are dropped; the raw value stays on the link.
+pub fn parse_rel(rel: &str) -> Vec<RelToken> {
+    let mut tokens = Vec::new();
+    for token in rel.split_ascii_whitespace().filter_map(RelToken::parse) {
+        if !tokens.contains(&token) {
+            tokens.push(token);
+        }
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_known_tokens_case_insensitively() {
+        assert_eq!(
+            parse_rel("NoFollow  noreferrer sponsored nofollow"),
+            vec![RelToken::Nofollow, RelToken::Sponsored]
+        );
+        assert_eq!(parse_rel("previous"), vec![RelToken::Prev]);
+        assert!(parse_rel("stylesheet").is_empty());
+    }
+
+    #[test]
+    fn tokens_round_trip_through_names() {
+        for token in RelToken::ALL {
+            assert_eq!(RelToken::parse(token.as_str()), Some(token));
+        }
+    }
+}
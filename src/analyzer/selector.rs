@@ -0,0 +1,130 @@
+use dom_content_extraction::scraper::ElementRef;
+
+/// Classes kept per step; more only make selectors longer, not more precise.
+const MAX_CLASSES_PER_STEP: usize = 2;
+
+/// CSS selector path to an element plus a generalized form that matches its
+/// repeating siblings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelectorPath {
+    /// `main > div.posts > article:nth-of-type(3) > h2 > a`
+    pub exact: String,
+    /// `main > div.posts > article > h2 > a`
+    pub generalized: String,
+}
+
+/// Builds the child-combinator path from the nearest anchor (an ancestor with
+/// a stable `id`, `<main>`, or `<body>`) down to `element`. Classes and ids
+/// containing digits are skipped as likely generated.
+pub fn selector_path(element: ElementRef<'_>) -> SelectorPath {
+    let mut exact = Vec::new();
+    let mut generalized = Vec::new();
+    let mut current = Some(element);
+    while let Some(node) = current {
+        let value = node.value();
+        let tag = value.name();
+        if let Some(id) = value.attr("id").filter(|id| is_stable_name(id)) {
+            exact.push(format!("{tag}#{id}"));
+            generalized.push(format!("{tag}#{id}"));
+            break;
+        }
+        if matches!(tag, "main" | "body" | "html") {
+            exact.push(tag.to_string());
+            generalized.push(tag.to_string());
+            break;
+        }
+
+        let mut step = tag.to_string();
+        for class in value
+            .classes()
+            .filter(|class| is_stable_name(class))
+            .take(MAX_CLASSES_PER_STEP)
+        {
+            step.push('.');
+            step.push_str(class);
+        }
+        generalized.push(step.clone());
+        if let Some(position) = nth_of_type(node) {
+            step.push_str(&format!(":nth-of-type({position})"));
+        }
+        exact.push(step);
+
+        current = node.parent().and_then(ElementRef::wrap);
+    }
+
+    exact.reverse();
+    generalized.reverse();
+    SelectorPath {
+        exact: exact.join(" > "),
+        generalized: generalized.join(" > "),
+    }
+}
+
+/// 1-based position among same-tag siblings; `None` when the tag is unique.
+fn nth_of_type(element: ElementRef<'_>) -> Option<usize> {
+    let parent = element.parent()?;
+    let tag = element.value().name();
+    let same_tag: Vec<_> = parent
+        .children()
+        .filter_map(ElementRef::wrap)
+        .filter(|sibling| sibling.value().name() == tag)
+        .collect();
+    if same_tag.len() < 2 {
+        return None;
+    }
+    same_tag
+        .iter()
+        .position(|sibling| sibling.id() == element.id())
+        .map(|index| index + 1)
+}
+
+fn is_stable_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('-')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphabetic() || c == '-' || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use dom_content_extraction::scraper::{Html, Selector};
+
+    use super::*;
+
+    fn paths(html: &str) -> Vec<SelectorPath> {
+        let doc = Html::parse_document(html);
+        let selector = Selector::parse("a").unwrap();
+        doc.select(&selector).map(selector_path).collect()
+    }
+
+    #[test]
+    fn listing_items_share_generalized_selector() {
+        let html = r#"<main><div class="posts">
+            <article class="post post-17"><h2><a href="/a">A</a></h2></article>
+            <article class="post post-18"><h2><a href="/b">B</a></h2></article>
+            </div></main>"#;
+        let paths = paths(html);
+        assert_eq!(
+            paths[1].exact,
+            "main > div.posts > article.post:nth-of-type(2) > h2 > a"
+        );
+        assert_eq!(
+            paths[0].generalized,
+            "main > div.posts > article.post > h2 > a"
+        );
+        assert_eq!(paths[0].generalized, paths[1].generalized);
+        assert_ne!(paths[0].exact, paths[1].exact);
+    }
+
+    #[test]
+    fn stops_at_stable_id_and_skips_generated_names() {
+        let html = r#"<div id="sidebar"><ul class="css-1x2y links">
+            <li><a href="/a">A</a></li></ul></div>
+            <div id="item-42"><a href="/b">B</a></div>"#;
+        let paths = paths(html);
+        assert_eq!(paths[0].exact, "div#sidebar > ul.links > li > a");
+        assert_eq!(paths[1].exact, "body > div:nth-of-type(2) > a");
+        assert_eq!(paths[1].generalized, "body > div > a");
+    }
+}
@@ -3,7 +3,12 @@ use std::collections::{BTreeMap, HashMap, HashSet};
 use serde::{Deserialize, Serialize};
 
 use crate::analyzer::date_kind::DateKind;
-use crate::analyzer::link::Link;
+use crate::analyzer::link::{self, Link, LinkScope};
+use crate::analyzer::locale::{self, PathLocales};
+use crate::analyzer::query_params::{self, QueryParam};
+use crate::analyzer::region::LinkRegion;
+use crate::analyzer::url_template::{self, UrlTemplate};
+use crate::normalize::TrackingParams;
 
 const UTILITY_KEYWORDS: &[&str] = &[
     "about",
@@ -23,11 +28,104 @@ const UTILITY_KEYWORDS: &[&str] = &[
 
 const MAX_TOP_SEGMENTS: usize = 20;
 const MAX_URL_SAMPLES_PER_SECTION: usize = 8;
+const MAX_ANCHOR_SAMPLES: usize = 5;
+const MAX_HOSTS: usize = 20;
+const MAX_REPEATED_URLS: usize = 20;
+const MAX_LOCALES: usize = 20;
+
+/// Navigational links pointing at one host.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HostCount {
+    pub host: String,
+    pub scope: LinkScope,
+    pub count: usize,
+}
+
+/// Anchor text summary for a group of links.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AnchorStats {
+    /// Most frequent anchor texts, most common first.
+    pub samples: Vec<String>,
+    /// Average length in characters of non-empty anchor texts.
+    pub avg_len: f64,
+    /// Share of links with no text and no image.
+    pub empty_share: f64,
+    /// Share of links whose only content is an image or icon.
+    pub image_share: f64,
+}
+
+impl AnchorStats {
+    pub fn from_links(links: &[&Link]) -> Self {
+        if links.is_empty() {
+            return Self::default();
+        }
+        let mut counts: Vec<(&str, usize)> = Vec::new();
+        let mut total_len = 0;
+        let mut with_text = 0;
+        let mut image = 0;
+        for link in links {
+            match link.text.as_deref() {
+                Some(text) => {
+                    with_text += 1;
+                    total_len += text.chars().count();
+                    match counts.iter_mut().find(|(seen, _)| *seen == text) {
+                        Some((_, count)) => *count += 1,
+                        None => counts.push((text, 1)),
+                    }
+                }
+                None if link.image_only => image += 1,
+                None => {}
+            }
+        }
+        // Stable sort keeps first appearance order among equal counts.
+        counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+
+        let share = |n: usize| round(n as f64 / links.len() as f64, 100.0);
+        Self {
+            samples: counts
+                .into_iter()
+                .take(MAX_ANCHOR_SAMPLES)
+                .map(|(text, _)| text.to_string())
+                .collect(),
+            avg_len: if with_text == 0 {
+                0.0
+            } else {
+                round(total_len as f64 / with_text as f64, 10.0)
+            },
+            empty_share: share(links.len() - with_text - image),
+            image_share: share(image),
+        }
+    }
+}
+
+fn round(value: f64, scale: f64) -> f64 {
+    (value * scale).round() / scale
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UrlFacts {
     pub total_internal: usize,
     pub total_external: usize,
+    /// Internal links on exactly the page host.
+    pub total_same_host: usize,
+    /// Internal links on sibling subdomains.
+    pub total_subdomain: usize,
+
+    /// Links per host, most linked first.
+    pub host_counts: Vec<HostCount>,
+    /// External registered domains, most linked first.
+    pub top_external_domains: Vec<(String, usize)>,
+
+    /// Navigational links per page region.
+    pub region_counts: BTreeMap<LinkRegion, usize>,
+
+    /// URLs linked more than once, most repeated first.
+    pub repeated_urls: Vec<(String, usize)>,
+
+    /// Locales from path prefixes (`/en/`) and language subdomains
+    /// (`de.example.com`) of internal links, most linked first. Path
+    /// prefixes are dropped before grouping into sections.
+    pub locales: Vec<(String, usize)>,
 
     pub depth_distribution: BTreeMap<usize, usize>,
 
@@ -35,29 +133,96 @@ pub struct UrlFacts {
 
     pub url_samples_by_section: BTreeMap<String, Vec<String>>,
 
+    pub anchor_stats_by_section: BTreeMap<String, AnchorStats>,
+
+    /// Internal URL paths clustered into templates, largest first.
+    pub url_templates: Vec<UrlTemplate>,
+
+    /// Query parameters across internal links, most frequent first.
+    pub query_params: Vec<QueryParam>,
+
     pub date_positions: Vec<(usize, DateKind)>,
 
     pub likely_utility_urls: Vec<String>,
 }
 
 impl UrlFacts {
-    pub fn from_links(links: &[Link], _page_domain: &str) -> Self {
+    /// Facts over navigational links (`<a>`, `<area>`); embeds and `<link>`
+    /// tags are ignored, and asset links count only toward the totals.
+    /// Query parameters matched by `tracking` get the tracking role.
+    pub fn from_links(
+        links: &[Link],
+        _page_domain: &str,
+        tracking: &TrackingParams,
+    ) -> Self {
+        let hreflangs: Vec<&str> =
+            links.iter().filter_map(|l| l.hreflang.as_deref()).collect();
+        let links: Vec<&Link> = links
+            .iter()
+            .filter(|l| l.element.is_navigational())
+            .collect();
         let total_internal = links.iter().filter(|l| l.is_internal).count();
         let total_external = links.len() - total_internal;
-
-        let internal: Vec<&Link> = links.iter().filter(|l| l.is_internal).collect();
+        let total_same_host = links
+            .iter()
+            .filter(|l| l.scope == LinkScope::SameHost)
+            .count();
+        let total_subdomain = links
+            .iter()
+            .filter(|l| l.scope == LinkScope::Subdomain)
+            .count();
+        let host_counts = host_counts(&links);
+        let mut external_domains: HashMap<String, usize> = HashMap::new();
+        for link in links.iter().filter(|l| l.scope == LinkScope::External) {
+            if let Some(domain) = link::extract_registered_domain(&link.url)
+                .or_else(|| link.url.host_str().map(String::from))
+            {
+                *external_domains.entry(domain).or_insert(0) += 1;
+            }
+        }
+        let top_external_domains = top_by_count(&external_domains, MAX_HOSTS);
+        let mut region_counts: BTreeMap<LinkRegion, usize> = BTreeMap::new();
+        let mut url_counts: HashMap<String, usize> = HashMap::new();
+        for link in &links {
+            *region_counts.entry(link.region).or_insert(0) += 1;
+            *url_counts.entry(link.url.to_string()).or_insert(0) += 1;
+        }
+        url_counts.retain(|_, count| *count > 1);
+        let repeated_urls = top_by_count(&url_counts, MAX_REPEATED_URLS);
+
+        // URL structure covers pages only; linked PDFs and images would
+        // otherwise show up as sections of their own.
+        let internal: Vec<&Link> = links
+            .iter()
+            .copied()
+            .filter(|l| l.is_internal && !l.is_asset())
+            .collect();
+
+        let path_locales = PathLocales::detect(
+            internal.iter().map(|link| &link.url),
+            hreflangs.iter().copied(),
+        );
 
         let mut depth_distribution: BTreeMap<usize, usize> = BTreeMap::new();
         let mut first_segment_counts: HashMap<String, usize> = HashMap::new();
         let mut url_samples_by_section: BTreeMap<String, HashSet<String>> =
             BTreeMap::new();
+        let mut links_by_section: BTreeMap<String, Vec<&Link>> = BTreeMap::new();
         let mut segments_by_depth: BTreeMap<usize, Vec<Vec<String>>> =
             BTreeMap::new();
         let mut utility_urls: HashSet<String> = HashSet::new();
+        let mut locale_counts: HashMap<String, usize> = HashMap::new();
 
         for link in &internal {
             let parsed = &link.url;
-            let segments = path_segments(parsed);
+            if let Some(locale) = path_locales.url_locale(parsed) {
+                *locale_counts.entry(locale).or_insert(0) += 1;
+            }
+            let mut segments = path_segments(parsed);
+            // `/en/blog/post` groups with `/blog/post`.
+            if path_locales.of(parsed).is_some() {
+                segments.remove(0);
+            }
             let depth = segments.len();
 
             if depth == 0 {
@@ -73,6 +238,10 @@ impl UrlFacts {
                     url_samples_by_section.entry(first.clone()).or_default();
                 let path = parsed.path().to_string();
                 samples.insert(path);
+                links_by_section
+                    .entry(first.clone())
+                    .or_default()
+                    .push(link);
             }
 
             if is_utility_url(&segments) {
@@ -84,7 +253,16 @@ impl UrlFacts {
 
         let top_first_segments =
             top_by_count(&first_segment_counts, MAX_TOP_SEGMENTS);
+        let locales = top_by_count(&locale_counts, MAX_LOCALES);
         let date_positions = detect_date_positions(&segments_by_depth);
+        let url_templates = url_template::cluster_urls(
+            internal.iter().map(|link| &link.url),
+            &path_locales,
+        );
+        let query_params = query_params::analyze_query_params(
+            internal.iter().map(|link| &link.url),
+            tracking,
+        );
 
         let url_samples_by_section: BTreeMap<String, Vec<String>> =
             url_samples_by_section
@@ -96,6 +274,10 @@ impl UrlFacts {
                     (k, v)
                 })
                 .collect();
+        let anchor_stats_by_section = links_by_section
+            .into_iter()
+            .map(|(section, links)| (section, AnchorStats::from_links(&links)))
+            .collect();
         let mut likely_utility_urls: Vec<String> =
             utility_urls.into_iter().collect();
         likely_utility_urls.sort();
@@ -103,9 +285,19 @@ impl UrlFacts {
         Self {
             total_internal,
             total_external,
+            total_same_host,
+            total_subdomain,
+            host_counts,
+            top_external_domains,
+            region_counts,
+            repeated_urls,
+            locales,
             depth_distribution,
             top_first_segments,
             url_samples_by_section,
+            anchor_stats_by_section,
+            url_templates,
+            query_params,
             date_positions,
             likely_utility_urls,
         }
@@ -122,8 +314,15 @@ impl UrlFacts {
 
         for (section, samples) in &self.url_samples_by_section {
             for sample in samples {
-                let segments: Vec<&str> =
+                let mut segments: Vec<&str> =
                     sample.split('/').filter(|s| !s.is_empty()).collect();
+                // Sections and date positions leave out the locale prefix.
+                if segments.len() > 1
+                    && segments[0] != section.as_str()
+                    && locale::is_locale(segments[0])
+                {
+                    segments.remove(0);
+                }
 
                 let first = match segments.first() {
                     Some(s) => *s,
@@ -171,6 +370,26 @@ impl UrlFacts {
     }
 }
 
+fn host_counts(links: &[&Link]) -> Vec<HostCount> {
+    let mut counts: Vec<HostCount> = Vec::new();
+    for link in links {
+        let Some(host) = link.url.host_str() else {
+            continue;
+        };
+        match counts.iter_mut().find(|count| count.host == host) {
+            Some(count) => count.count += 1,
+            None => counts.push(HostCount {
+                host: host.to_string(),
+                scope: link.scope,
+                count: 1,
+            }),
+        }
+    }
+    counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.host.cmp(&b.host)));
+    counts.truncate(MAX_HOSTS);
+    counts
+}
+
 fn path_segments(url: &url::Url) -> Vec<String> {
     url.path_segments()
         .map(|segs| segs.filter(|s| !s.is_empty()).map(String::from).collect())
@@ -252,6 +471,8 @@ fn is_utility_url(segments: &[String]) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::analyzer::link::LinkElement;
+    use crate::analyzer::link_kind;
 
     fn make_link(url: &str, is_internal: bool) -> Link {
         Link {
@@ -259,13 +480,193 @@ mod tests {
             url: url::Url::parse(url).unwrap(),
             text: None,
             rel: None,
+            rel_tokens: Vec::new(),
+            target: None,
+            hreflang: None,
+            media_type: None,
+            download: None,
+            title: None,
             is_internal,
+            scope: if is_internal {
+                LinkScope::SameHost
+            } else {
+                LinkScope::External
+            },
+            kind: link_kind::link_kind(
+                &url::Url::parse(url).unwrap(),
+                LinkElement::A,
+                None,
+                None,
+            ),
+            element: LinkElement::A,
+            attribute: "href".to_string(),
+            region: LinkRegion::Other,
+            selector: "body > a".to_string(),
+            generalized_selector: "body > a".to_string(),
+            image_only: false,
         }
     }
 
+    #[test]
+    fn ignores_non_navigational_links() {
+        let mut image = make_link("https://example.com/img/logo.png", true);
+        image.element = crate::analyzer::link::LinkElement::Img;
+        let links = vec![make_link("https://example.com/news/a", true), image];
+
+        let facts =
+            UrlFacts::from_links(&links, "example.com", &TrackingParams::default());
+        assert_eq!(facts.total_internal, 1);
+        assert!(!facts.url_samples_by_section.contains_key("img"));
+    }
+
+    #[test]
+    fn counts_links_per_region() {
+        let mut nav = make_link("https://example.com/about", true);
+        nav.region = LinkRegion::Nav;
+        let mut footer = make_link("https://example.com/privacy", true);
+        footer.region = LinkRegion::Footer;
+        let mut main = make_link("https://other.com/a", false);
+        main.region = LinkRegion::Main;
+        let links = vec![nav.clone(), nav, footer, main];
+
+        let facts =
+            UrlFacts::from_links(&links, "example.com", &TrackingParams::default());
+        assert_eq!(facts.region_counts.get(&LinkRegion::Nav), Some(&2));
+        assert_eq!(facts.region_counts.get(&LinkRegion::Footer), Some(&1));
+        assert_eq!(facts.region_counts.get(&LinkRegion::Main), Some(&1));
+        assert!(!facts.region_counts.contains_key(&LinkRegion::Other));
+    }
+
+    #[test]
+    fn anchor_stats_per_section() {
+        let mut headline = make_link("https://example.com/news/big-story", true);
+        headline.text = Some("A long headline about the story".to_string());
+        let mut repeat = make_link("https://example.com/news/big-story", true);
+        repeat.text = Some("A long headline about the story".to_string());
+        let mut more = make_link("https://example.com/news/other", true);
+        more.text = Some("More".to_string());
+        let mut image = make_link("https://example.com/news/photo", true);
+        image.image_only = true;
+        let links = vec![headline, repeat, more, image];
+
+        let facts =
+            UrlFacts::from_links(&links, "example.com", &TrackingParams::default());
+        let stats = &facts.anchor_stats_by_section["news"];
+        assert_eq!(stats.samples, ["A long headline about the story", "More"]);
+        assert_eq!(stats.avg_len, 22.0);
+        assert_eq!(stats.image_share, 0.25);
+        assert_eq!(stats.empty_share, 0.0);
+
+        let empty = make_link("https://example.com/news/x", true);
+        assert_eq!(AnchorStats::from_links(&[&empty]).empty_share, 1.0);
+    }
+
+    #[test]
+    fn breaks_down_hosts_and_external_domains() {
+        let mut blog = make_link("https://blog.example.com/post", true);
+        blog.scope = LinkScope::Subdomain;
+        let links = vec![
+            make_link("https://example.com/a", true),
+            make_link("https://example.com/b", true),
+            blog,
+            make_link("https://www.other.com/x", false),
+            make_link("https://cdn.other.com/y", false),
+            make_link("https://third.org/", false),
+            make_link("mailto:hi@example.com", false),
+        ];
+
+        let facts =
+            UrlFacts::from_links(&links, "example.com", &TrackingParams::default());
+        assert_eq!(facts.total_same_host, 2);
+        assert_eq!(facts.total_subdomain, 1);
+        assert_eq!(
+            facts.host_counts[0],
+            HostCount {
+                host: "example.com".to_string(),
+                scope: LinkScope::SameHost,
+                count: 2,
+            }
+        );
+        assert!(facts.host_counts.iter().any(|count| {
+            count.host == "blog.example.com" && count.scope == LinkScope::Subdomain
+        }));
+        assert_eq!(
+            facts.top_external_domains,
+            [("other.com".to_string(), 2), ("third.org".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn assets_are_left_out_of_sections() {
+        let links = vec![
+            make_link("https://example.com/blog/post", true),
+            make_link("https://example.com/files/report.pdf", true),
+            make_link("https://example.com/files/photo.jpg", true),
+        ];
+        let facts =
+            UrlFacts::from_links(&links, "example.com", &TrackingParams::default());
+        assert_eq!(facts.total_internal, 3);
+        assert_eq!(facts.top_first_segments, [("blog".to_string(), 1)]);
+        assert!(!facts.url_samples_by_section.contains_key("files"));
+    }
+
+    #[test]
+    fn collapses_locale_prefixes_and_reports_locales() {
+        let mut german = make_link("https://de.example.com/blog/drei", true);
+        german.scope = LinkScope::Subdomain;
+        let links = vec![
+            make_link("https://example.com/en/blog/one", true),
+            make_link("https://example.com/ru-ru/blog/two", true),
+            make_link("https://example.com/blog/three", true),
+            make_link("https://example.com/en/", true),
+            german,
+        ];
+        let facts =
+            UrlFacts::from_links(&links, "example.com", &TrackingParams::default());
+        assert_eq!(facts.top_first_segments, [("blog".to_string(), 4)]);
+        assert_eq!(
+            facts.locales,
+            [
+                ("en".to_string(), 2),
+                ("de".to_string(), 1),
+                ("ru-ru".to_string(), 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn lone_ambiguous_locale_code_is_a_section() {
+        let links = vec![
+            make_link("https://example.com/id/123", true),
+            make_link("https://example.com/id/456", true),
+        ];
+        let facts =
+            UrlFacts::from_links(&links, "example.com", &TrackingParams::default());
+        assert_eq!(facts.top_first_segments, [("id".to_string(), 2)]);
+        assert!(facts.locales.is_empty());
+        assert_eq!(facts.url_templates[0].template, "/id/{id}");
+    }
+
+    #[test]
+    fn counts_repeated_urls() {
+        let links = vec![
+            make_link("https://example.com/a", true),
+            make_link("https://example.com/b", true),
+            make_link("https://example.com/a", true),
+            make_link("https://example.com/a", true),
+        ];
+        let facts =
+            UrlFacts::from_links(&links, "example.com", &TrackingParams::default());
+        assert_eq!(
+            facts.repeated_urls,
+            [("https://example.com/a".to_string(), 3)]
+        );
+    }
+
     #[test]
     fn test_empty_links() {
-        let facts = UrlFacts::from_links(&[], "example.com");
+        let facts =
+            UrlFacts::from_links(&[], "example.com", &TrackingParams::default());
         assert_eq!(facts.total_internal, 0);
         assert_eq!(facts.total_external, 0);
         assert!(facts.depth_distribution.is_empty());
@@ -278,7 +679,8 @@ mod tests {
             make_link("https://example.com/tech/eth", true),
             make_link("https://other.com/page", false),
         ];
-        let facts = UrlFacts::from_links(&links, "example.com");
+        let facts =
+            UrlFacts::from_links(&links, "example.com", &TrackingParams::default());
         assert_eq!(facts.total_internal, 2);
         assert_eq!(facts.total_external, 1);
     }
@@ -291,7 +693,8 @@ mod tests {
             make_link("https://example.com/a/b/c", true),
             make_link("https://example.com/a/b/c/d", true),
         ];
-        let facts = UrlFacts::from_links(&links, "example.com");
+        let facts =
+            UrlFacts::from_links(&links, "example.com", &TrackingParams::default());
         assert_eq!(facts.depth_distribution.get(&1), Some(&1));
         assert_eq!(facts.depth_distribution.get(&2), Some(&1));
         assert_eq!(facts.depth_distribution.get(&3), Some(&1));
@@ -305,7 +708,8 @@ mod tests {
             make_link("https://example.com/markets/eth", true),
             make_link("https://example.com/tech/ai", true),
         ];
-        let facts = UrlFacts::from_links(&links, "example.com");
+        let facts =
+            UrlFacts::from_links(&links, "example.com", &TrackingParams::default());
         assert_eq!(facts.top_first_segments[0], ("markets".to_string(), 2));
         assert_eq!(facts.top_first_segments[1], ("tech".to_string(), 1));
     }
@@ -317,7 +721,8 @@ mod tests {
             make_link("https://example.com/markets/eth", true),
             make_link("https://example.com/tech/ai", true),
         ];
-        let facts = UrlFacts::from_links(&links, "example.com");
+        let facts =
+            UrlFacts::from_links(&links, "example.com", &TrackingParams::default());
         let markets_samples = facts.url_samples_by_section.get("markets").unwrap();
         assert_eq!(markets_samples.len(), 2);
         assert!(
@@ -333,7 +738,8 @@ mod tests {
             make_link("https://example.com/tech/2026/04/05/ai", true),
             make_link("https://example.com/policy/2025/12/28/law", true),
         ];
-        let facts = UrlFacts::from_links(&links, "example.com");
+        let facts =
+            UrlFacts::from_links(&links, "example.com", &TrackingParams::default());
         assert!(facts.date_positions.contains(&(1, DateKind::Year)));
         assert!(facts.date_positions.contains(&(2, DateKind::Month)));
         assert!(facts.date_positions.contains(&(3, DateKind::Day)));
@@ -346,7 +752,8 @@ mod tests {
             make_link("https://example.com/privacy", true),
             make_link("https://example.com/markets/btc", true),
         ];
-        let facts = UrlFacts::from_links(&links, "example.com");
+        let facts =
+            UrlFacts::from_links(&links, "example.com", &TrackingParams::default());
         assert_eq!(facts.likely_utility_urls.len(), 2);
     }
 
@@ -362,7 +769,8 @@ mod tests {
                 true,
             ),
         ];
-        let facts = UrlFacts::from_links(&links, "example.com");
+        let facts =
+            UrlFacts::from_links(&links, "example.com", &TrackingParams::default());
         let pattern = facts.detected_url_pattern();
         assert!(pattern.is_some());
         let p = pattern.unwrap();
@@ -371,4 +779,28 @@ mod tests {
         assert!(p.contains("{day}"));
         assert!(p.contains("{slug}"));
     }
+
+    #[test]
+    fn detects_url_pattern_behind_locale_prefix() {
+        let mut alternate = make_link("https://example.com/en/", true);
+        alternate.element = LinkElement::Link;
+        alternate.hreflang = Some("en".to_string());
+        let links = vec![
+            alternate,
+            make_link(
+                "https://example.com/en/news/2024/05/17/first-story-slug",
+                true,
+            ),
+            make_link(
+                "https://example.com/en/news/2024/05/16/second-story-slug",
+                true,
+            ),
+        ];
+        let facts =
+            UrlFacts::from_links(&links, "example.com", &TrackingParams::default());
+        assert_eq!(
+            facts.detected_url_pattern().as_deref(),
+            Some("/news/{year}/{month}/{day}/{slug}")
+        );
+    }
 }
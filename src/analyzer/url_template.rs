@@ -0,0 +1,354 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::analyzer::locale::PathLocales;
+
+const MAX_TEMPLATES: usize = 20;
+const MAX_TEMPLATE_SAMPLES: usize = 3;
+/// Distinct plain words at one position (after the first) that turn the
+/// position into `{slug}`: `/tag/rust`, `/tag/go`, `/tag/python`.
+const MIN_DISTINCT_WORDS: usize = 3;
+
+/// A cluster of URL paths sharing one template, such as
+/// `/news/{year}/{month}/{slug}-{id}.html`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UrlTemplate {
+    pub template: String,
+    /// Distinct paths matching the template.
+    pub count: usize,
+    pub samples: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    /// Segment with an inferred type, e.g. `{id}` or `{slug}-{id}.html`.
+    Typed(String),
+    /// Plain segment; becomes `{slug}` only when it varies across a cluster.
+    Word { value: String, slug_like: bool },
+}
+
+/// Clusters URL paths into templates, largest clusters first. Queries and
+/// fragments are ignored; the root path is skipped. Only prefixes confirmed in
+/// `locales` become `{locale}`.
+pub fn cluster_urls<'a>(
+    urls: impl IntoIterator<Item = &'a Url>,
+    locales: &PathLocales,
+) -> Vec<UrlTemplate> {
+    let paths: BTreeSet<String> = urls
+        .into_iter()
+        .map(|url| url.path().to_string())
+        .filter(|path| path.split('/').any(|segment| !segment.is_empty()))
+        .collect();
+    let tokenized: Vec<(String, Vec<Token>)> = paths
+        .into_iter()
+        .map(|path| {
+            let tokens = tokenize(&path, locales);
+            (path, tokens)
+        })
+        .collect();
+
+    // Paths with the same typed shape; plain words are decided per group.
+    let mut shapes: BTreeMap<Vec<Option<&str>>, Vec<usize>> = BTreeMap::new();
+    for (index, (_, tokens)) in tokenized.iter().enumerate() {
+        let shape = tokens
+            .iter()
+            .map(|token| match token {
+                Token::Typed(typed) => Some(typed.as_str()),
+                Token::Word { .. } => None,
+            })
+            .collect();
+        shapes.entry(shape).or_default().push(index);
+    }
+
+    let mut clusters: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+    for members in shapes.values() {
+        let generalized = generalized_positions(&tokenized, members);
+        for &index in members {
+            let (path, tokens) = &tokenized[index];
+            let mut template = String::new();
+            for (position, token) in tokens.iter().enumerate() {
+                template.push('/');
+                match token {
+                    Token::Typed(typed) => template.push_str(typed),
+                    Token::Word { .. } if generalized.contains(&position) => {
+                        template.push_str("{slug}")
+                    }
+                    Token::Word { value, .. } => template.push_str(value),
+                }
+            }
+            if path.ends_with('/') {
+                template.push('/');
+            }
+            clusters.entry(template).or_default().push(path);
+        }
+    }
+
+    let mut templates: Vec<UrlTemplate> = clusters
+        .into_iter()
+        .map(|(template, paths)| UrlTemplate {
+            template,
+            count: paths.len(),
+            samples: paths
+                .into_iter()
+                .take(MAX_TEMPLATE_SAMPLES)
+                .map(String::from)
+                .collect(),
+        })
+        .collect();
+    templates.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| a.template.cmp(&b.template))
+    });
+    templates.truncate(MAX_TEMPLATES);
+    templates
+}
+
+/// Word positions that vary within a shape group and look like slugs.
+fn generalized_positions(
+    tokenized: &[(String, Vec<Token>)],
+    members: &[usize],
+) -> BTreeSet<usize> {
+    let mut words: BTreeMap<usize, (BTreeSet<&str>, bool)> = BTreeMap::new();
+    for &index in members {
+        for (position, token) in tokenized[index].1.iter().enumerate() {
+            if let Token::Word { value, slug_like } = token {
+                let entry =
+                    words.entry(position).or_insert((BTreeSet::new(), true));
+                entry.0.insert(value);
+                entry.1 &= *slug_like;
+            }
+        }
+    }
+    words
+        .into_iter()
+        .filter(|(position, (values, all_slug_like))| {
+            values.len() > 1
+                && (*all_slug_like
+                    || (*position > 0 && values.len() >= MIN_DISTINCT_WORDS))
+        })
+        .map(|(position, _)| position)
+        .collect()
+}
+
+fn tokenize(path: &str, locales: &PathLocales) -> Vec<Token> {
+    let segments: Vec<&str> = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    let mut tokens: Vec<Token> = Vec::with_capacity(segments.len());
+    for (position, segment) in segments.iter().enumerate() {
+        let previous = tokens.last().and_then(|token| match token {
+            Token::Typed(typed) => Some(typed.as_str()),
+            Token::Word { .. } => None,
+        });
+        tokens.push(classify(segment, position, previous, locales));
+    }
+    tokens
+}
+
+/// Infers the type of one segment. `previous` is the template of the segment
+/// before it, so `2024/05/17` reads as year, month, day.
+fn classify(
+    segment: &str,
+    position: usize,
+    previous: Option<&str>,
+    locales: &PathLocales,
+) -> Token {
+    let (stem, extension) = split_extension(segment);
+    if let Some(kind) = classify_numeric(stem, previous) {
+        return Token::Typed(format!("{kind}{extension}"));
+    }
+    if position == 0 && extension.is_empty() && locales.contains(stem) {
+        return Token::Typed("{locale}".to_string());
+    }
+    if let Some(kind) = classify_atom(stem) {
+        return Token::Typed(format!("{kind}{extension}"));
+    }
+    // `big-story-123` -> `{slug}-{id}`
+    if let Some((head, tail)) = stem.rsplit_once('-')
+        && is_slug(head)
+        && let Some(kind) =
+            classify_atom(tail).or_else(|| is_digits(tail).then_some("{id}"))
+    {
+        return Token::Typed(format!("{{slug}}-{kind}{extension}"));
+    }
+    Token::Word {
+        value: segment.to_string(),
+        slug_like: stem.contains('-') && is_slug(stem),
+    }
+}
+
+fn classify_numeric(stem: &str, previous: Option<&str>) -> Option<&'static str> {
+    if !is_digits(stem) {
+        return None;
+    }
+    let value: u64 = stem.parse().ok()?;
+    let kind = match previous {
+        _ if stem.len() == 4 && (1900..=2100).contains(&value) => "{year}",
+        Some("{year}") if stem.len() <= 2 && (1..=12).contains(&value) => "{month}",
+        Some("{month}") if stem.len() <= 2 && (1..=31).contains(&value) => "{day}",
+        _ => "{id}",
+    };
+    Some(kind)
+}
+
+/// Self-describing segment types: uuid, `YYYY-MM-DD` date, hex hash.
+fn classify_atom(stem: &str) -> Option<&'static str> {
+    if is_uuid(stem) {
+        Some("{uuid}")
+    } else if is_date(stem) {
+        Some("{date}")
+    } else if is_hash(stem) {
+        Some("{hash}")
+    } else {
+        None
+    }
+}
+
+/// `story.html` -> (`story`, `.html`); dots elsewhere are kept in the stem.
+fn split_extension(segment: &str) -> (&str, &str) {
+    match segment.rfind('.') {
+        Some(dot)
+            if dot > 0
+                && segment.len() - dot <= 6
+                && segment[dot + 1..]
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric()) =>
+        {
+            segment.split_at(dot)
+        }
+        _ => (segment, ""),
+    }
+}
+
+pub(crate) fn is_digits(value: &str) -> bool {
+    !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit())
+}
+
+pub(crate) fn is_uuid(value: &str) -> bool {
+    let groups: Vec<&str> = value.split('-').collect();
+    groups.len() == 5
+        && groups
+            .iter()
+            .zip([8, 4, 4, 4, 12])
+            .all(|(group, len)| group.len() == len && is_hex(group))
+}
+
+fn is_date(value: &str) -> bool {
+    let parts: Vec<&str> = value.split('-').collect();
+    matches!(
+        parts.as_slice(),
+        [year, month, day]
+            if year.len() == 4 && month.len() == 2 && day.len() == 2
+                && parts.iter().all(|part| is_digits(part))
+    )
+}
+
+/// At least 8 hex digits mixing letters and digits (commit ids, content hashes).
+pub(crate) fn is_hash(value: &str) -> bool {
+    value.len() >= 8
+        && is_hex(value)
+        && value.bytes().any(|b| b.is_ascii_digit())
+        && value.bytes().any(|b| b.is_ascii_alphabetic())
+}
+
+fn is_hex(value: &str) -> bool {
+    value.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+fn is_slug(value: &str) -> bool {
+    !value.is_empty()
+        && value.bytes().any(|b| b.is_ascii_alphabetic())
+        && value
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn templates(paths: &[&str]) -> Vec<UrlTemplate> {
+        let urls: Vec<Url> = paths
+            .iter()
+            .map(|path| Url::parse(&format!("https://example.com{path}")).unwrap())
+            .collect();
+        cluster_urls(&urls, &PathLocales::detect(&urls, []))
+    }
+
+    #[test]
+    fn clusters_dated_articles_with_slug_and_id() {
+        let result = templates(&[
+            "/news/2026/04/06/big-story-123.html",
+            "/news/2026/04/05/other-story-456.html",
+            "/news/2025/12/31/year-end-789.html?utm_source=x",
+            "/about",
+        ]);
+        assert_eq!(
+            result[0].template,
+            "/news/{year}/{month}/{day}/{slug}-{id}.html"
+        );
+        assert_eq!(result[0].count, 3);
+        assert_eq!(result[0].samples.len(), 3);
+        assert_eq!(result[1].template, "/about");
+    }
+
+    #[test]
+    fn infers_segment_types() {
+        let result = templates(&[
+            "/en-us/docs/550e8400-e29b-41d4-a716-446655440000",
+            "/commit/3f2a9c1b7d",
+            "/events/2024-05-17",
+            "/p/12345",
+        ]);
+        let names: Vec<&str> = result
+            .iter()
+            .map(|template| template.template.as_str())
+            .collect();
+        assert!(names.contains(&"/{locale}/docs/{uuid}"));
+        assert!(names.contains(&"/commit/{hash}"));
+        assert!(names.contains(&"/events/{date}"));
+        assert!(names.contains(&"/p/{id}"));
+    }
+
+    #[test]
+    fn lone_ambiguous_locale_code_stays_a_section() {
+        let result = templates(&["/id/123", "/id/456"]);
+        assert_eq!(result[0].template, "/id/{id}");
+        assert_eq!(result[0].count, 2);
+    }
+
+    #[test]
+    fn generalizes_varying_words_but_keeps_sections() {
+        let result = templates(&[
+            "/tag/rust",
+            "/tag/go",
+            "/tag/python",
+            "/about",
+            "/contact",
+            "/blog/first-post/",
+            "/blog/second-post/",
+        ]);
+        assert_eq!(result[0].template, "/tag/{slug}");
+        assert_eq!(result[0].count, 3);
+        assert_eq!(result[1].template, "/blog/{slug}/");
+        assert_eq!(result[1].count, 2);
+        assert!(result.iter().any(|template| template.template == "/about"));
+        assert!(
+            result
+                .iter()
+                .any(|template| template.template == "/contact")
+        );
+    }
+
+    #[test]
+    fn counts_distinct_paths_and_skips_root() {
+        let result = templates(&["/", "/p/1", "/p/1#top", "/p/2?page=2"]);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].template, "/p/{id}");
+        assert_eq!(result[0].count, 2);
+    }
+}
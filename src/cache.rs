@@ -1,9 +1,26 @@
+mod compression;
 mod error;
 mod key;
+mod root;
+#[cfg(feature = "sqlite")]
+mod sqlite;
 mod store;
 mod types;
+mod warc;
 
+pub use compression::Compression;
 pub use error::CacheError;
 pub use key::normalize_url;
-pub use store::{Cache, FileCache};
-pub use types::{CacheConfig, CachedFetch, CachedPage};
+#[allow(unused_imports)]
+pub use root::{discover_root, locate_root, user_cache_root};
+#[cfg(feature = "sqlite")]
+#[allow(unused_imports)]
+pub use sqlite::SqliteCache;
+#[allow(unused_imports)]
+pub use store::{Cache, FileCache, open};
+pub use types::{
+    CacheBackend, CacheConfig, CacheQuery, CacheStats, CachedFailure, CachedFetch,
+    CachedPage, DEFAULT_FAILURE_TTL_SECS, DEFAULT_HISTORY_LIMIT, FailureKind,
+};
+#[allow(unused_imports)]
+pub use warc::{ImportSummary, export_to_path, import_from_path};
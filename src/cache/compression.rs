@@ -0,0 +1,105 @@
+use std::io::{self, Read, Write};
+
+use flate2::Compression as GzipLevel;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+
+const ZSTD_LEVEL: i32 = 3;
+
+/// Storage codec for cached page bodies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Compression {
+    #[default]
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    pub const ALL: [Compression; 3] =
+        [Compression::None, Compression::Gzip, Compression::Zstd];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "none" => Some(Self::None),
+            "gzip" => Some(Self::Gzip),
+            "zstd" => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Gzip => "gzip",
+            Self::Zstd => "zstd",
+        }
+    }
+
+    /// File name used for a page body stored with this codec.
+    pub fn body_file_name(self) -> &'static str {
+        match self {
+            Self::None => "page.html",
+            Self::Gzip => "page.html.gz",
+            Self::Zstd => "page.html.zst",
+        }
+    }
+
+    pub fn encode(self, data: &[u8]) -> io::Result<Vec<u8>> {
+        match self {
+            Self::None => Ok(data.to_vec()),
+            Self::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), GzipLevel::default());
+                encoder.write_all(data)?;
+                encoder.finish()
+            }
+            Self::Zstd => zstd::encode_all(data, ZSTD_LEVEL),
+        }
+    }
+
+    pub fn decode(self, data: &[u8]) -> io::Result<Vec<u8>> {
+        match self {
+            Self::None => Ok(data.to_vec()),
+            Self::Gzip => {
+                let mut out = Vec::new();
+                GzDecoder::new(data).read_to_end(&mut out)?;
+                Ok(out)
+            }
+            Self::Zstd => zstd::decode_all(data),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_known_codecs() {
+        assert_eq!(Compression::parse("none"), Some(Compression::None));
+        assert_eq!(Compression::parse("gzip"), Some(Compression::Gzip));
+        assert_eq!(Compression::parse("zstd"), Some(Compression::Zstd));
+        assert_eq!(Compression::parse("brotli"), None);
+    }
+
+    #[test]
+    fn every_codec_round_trips() {
+        let body = "<html><body>hello</body></html>".repeat(50);
+        for codec in Compression::ALL {
+            let encoded = codec.encode(body.as_bytes()).unwrap();
+            let decoded = codec.decode(&encoded).unwrap();
+            assert_eq!(decoded, body.as_bytes(), "codec {}", codec.as_str());
+        }
+    }
+
+    #[test]
+    fn compressed_codecs_shrink_repetitive_html() {
+        let body = "<div class=\"item\">news</div>".repeat(200);
+        assert!(
+            Compression::Gzip.encode(body.as_bytes()).unwrap().len() < body.len()
+        );
+        assert!(
+            Compression::Zstd.encode(body.as_bytes()).unwrap().len() < body.len()
+        );
+    }
+}
@@ -13,4 +13,25 @@ pub enum CacheError {
 
     #[error("cache version mismatch: expected {expected}, found {found}")]
     VersionMismatch { expected: u32, found: String },
+
+    #[error(
+        "snapshot {index} of {url} is not cached ({available} available); \
+         refetch with --refresh to record a new one"
+    )]
+    MissingSnapshot {
+        url: String,
+        index: usize,
+        available: usize,
+    },
+
+    #[error("invalid WARC: {0}")]
+    Warc(String),
+
+    #[cfg_attr(feature = "sqlite", allow(dead_code))]
+    #[error("cache backend not available: {0}")]
+    BackendUnavailable(String),
+
+    #[cfg(feature = "sqlite")]
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
 }
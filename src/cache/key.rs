@@ -2,6 +2,7 @@ use sha2::{Digest, Sha256};
 use url::Url;
 
 use crate::cache::CacheError;
+use crate::normalize::NormalizePolicy;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CacheKey {
@@ -10,8 +11,16 @@ pub struct CacheKey {
 }
 
 impl CacheKey {
+    #[allow(dead_code)]
     pub fn new(url: &str) -> Result<Self, CacheError> {
-        let normalized_final_url = normalize_url(url)?;
+        Self::with_policy(url, &NormalizePolicy::default())
+    }
+
+    pub fn with_policy(
+        url: &str,
+        policy: &NormalizePolicy,
+    ) -> Result<Self, CacheError> {
+        let normalized_final_url = normalize_url_with(url, policy)?;
         let hash = hash_url(&normalized_final_url);
         Ok(Self {
             normalized_final_url,
@@ -21,18 +30,16 @@ impl CacheKey {
 }
 
 pub fn normalize_url(url: &str) -> Result<String, CacheError> {
+    normalize_url_with(url, &NormalizePolicy::default())
+}
+
+pub fn normalize_url_with(
+    url: &str,
+    policy: &NormalizePolicy,
+) -> Result<String, CacheError> {
     let mut parsed =
         Url::parse(url).map_err(|e| CacheError::InvalidUrl(e.to_string()))?;
-
-    parsed.set_fragment(None);
-
-    if matches!(
-        (parsed.scheme(), parsed.port()),
-        ("http", Some(80)) | ("https", Some(443))
-    ) {
-        let _ = parsed.set_port(None);
-    }
-
+    policy.apply(&mut parsed);
     Ok(parsed.to_string())
 }
 
@@ -53,4 +60,21 @@ mod tests {
             normalize_url("https://Example.com:443/path?q=1#frag").unwrap();
         assert_eq!(normalized, "https://example.com/path?q=1");
     }
+
+    #[test]
+    fn policy_merges_variants_into_one_key() {
+        let policy = NormalizePolicy::all();
+        let plain = CacheKey::with_policy("https://example.com/a?x=1&y=2", &policy)
+            .unwrap();
+        let noisy = CacheKey::with_policy(
+            "https://example.com/a/index.html?y=2&utm_source=nl&x=1",
+            &policy,
+        )
+        .unwrap();
+        assert_eq!(plain, noisy);
+        assert_ne!(
+            plain,
+            CacheKey::new("https://example.com/a/?y=2&x=1").unwrap()
+        );
+    }
 }
@@ -0,0 +1,85 @@
+use std::path::{Path, PathBuf};
+
+use crate::cache::types::CACHE_DIR;
+
+/// Picks the cache root: an explicit directory wins, then the user cache when
+/// requested, then the nearest existing `.pginf/` walking up from `cwd`, and
+/// finally `.pginf/` in `cwd`.
+pub fn locate_root(
+    explicit: Option<&Path>,
+    user_cache: bool,
+    cwd: &Path,
+) -> PathBuf {
+    if let Some(dir) = explicit {
+        return dir.to_path_buf();
+    }
+    if user_cache && let Some(dir) = user_cache_root() {
+        return dir;
+    }
+    discover_root(cwd).unwrap_or_else(|| cwd.join(CACHE_DIR))
+}
+
+/// Nearest existing `.pginf/` in `start` or its ancestors.
+pub fn discover_root(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(CACHE_DIR))
+        .find(|candidate| candidate.is_dir())
+}
+
+pub fn user_cache_root() -> Option<PathBuf> {
+    dirs::cache_dir().map(|dir| dir.join("pginf"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::*;
+
+    fn temp_project() -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        std::env::temp_dir().join(format!("pageinfo-root-test-{nanos}"))
+    }
+
+    #[test]
+    fn discovers_cache_in_parent_directory() {
+        let project = temp_project();
+        let nested = project.join("src").join("deep");
+        fs::create_dir_all(&nested).unwrap();
+        fs::create_dir_all(project.join(CACHE_DIR)).unwrap();
+
+        assert_eq!(locate_root(None, false, &nested), project.join(CACHE_DIR));
+
+        fs::remove_dir_all(project).unwrap();
+    }
+
+    #[test]
+    fn explicit_dir_wins_over_discovery() {
+        let project = temp_project();
+        fs::create_dir_all(project.join(CACHE_DIR)).unwrap();
+
+        let explicit = project.join("elsewhere");
+        assert_eq!(locate_root(Some(&explicit), true, &project), explicit);
+
+        fs::remove_dir_all(project).unwrap();
+    }
+
+    #[test]
+    fn falls_back_to_local_cache_dir() {
+        let project = temp_project();
+        fs::create_dir_all(&project).unwrap();
+
+        let root = locate_root(None, false, &project);
+        // A `.pginf/` above the temp dir would be discovered instead.
+        if discover_root(&project).is_none() {
+            assert_eq!(root, project.join(CACHE_DIR));
+        }
+
+        fs::remove_dir_all(project).unwrap();
+    }
+}
@@ -0,0 +1,604 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use rusqlite::{Connection, OptionalExtension, params};
+
+use crate::cache::compression::Compression;
+use crate::cache::error::CacheError;
+use crate::cache::key::CacheKey;
+use crate::cache::store::{CACHE_VERSION, Cache, now_secs};
+use crate::cache::types::{
+    CacheConfig, CacheQuery, CacheStats, CachedFailure, CachedFetch, CachedPage,
+    registered_domain,
+};
+
+const DB_FILE: &str = "cache.sqlite";
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS meta (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS pages (
+    hash TEXT PRIMARY KEY,
+    normalized_final_url TEXT NOT NULL,
+    input_url TEXT NOT NULL,
+    final_url TEXT NOT NULL,
+    domain TEXT,
+    status INTEGER NOT NULL,
+    fetched_at INTEGER NOT NULL,
+    fetch_json TEXT NOT NULL,
+    headers_json TEXT NOT NULL,
+    body BLOB NOT NULL,
+    body_codec TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS pages_domain ON pages (domain);
+CREATE INDEX IF NOT EXISTS pages_final_url ON pages (final_url);
+CREATE INDEX IF NOT EXISTS pages_status ON pages (status);
+CREATE INDEX IF NOT EXISTS pages_fetched_at ON pages (fetched_at);
+CREATE TABLE IF NOT EXISTS history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    hash TEXT NOT NULL,
+    fetched_at INTEGER NOT NULL,
+    fetch_json TEXT NOT NULL,
+    headers_json TEXT NOT NULL,
+    body BLOB NOT NULL,
+    body_codec TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS history_hash ON history (hash, fetched_at);
+CREATE TABLE IF NOT EXISTS failures (
+    hash TEXT PRIMARY KEY,
+    failed_at INTEGER NOT NULL,
+    failure_json TEXT NOT NULL
+);
+";
+
+/// Single-file cache backend. Every entry is one row in `pages`, so writes
+/// are atomic and listing by domain, URL, status or date uses an index.
+#[derive(Debug, Clone)]
+pub struct SqliteCache {
+    config: CacheConfig,
+}
+
+impl SqliteCache {
+    pub fn new(config: CacheConfig) -> Self {
+        Self { config }
+    }
+
+    fn db_path(&self) -> PathBuf {
+        self.config.root_dir.join(DB_FILE)
+    }
+
+    fn connect(&self) -> Result<Connection, CacheError> {
+        let conn = Connection::open(self.db_path())?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        Ok(conn)
+    }
+
+    fn decode_body(codec: &str, body: &[u8]) -> Result<String, CacheError> {
+        let codec = Compression::parse(codec).unwrap_or_default();
+        String::from_utf8(codec.decode(body)?).map_err(|e| {
+            CacheError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+        })
+    }
+
+    fn decode_page(
+        fetch_json: &str,
+        headers_json: &str,
+        body: &[u8],
+        codec: &str,
+    ) -> Result<CachedPage, CacheError> {
+        Ok(CachedPage {
+            fetch: serde_json::from_str(fetch_json)?,
+            headers: serde_json::from_str(headers_json)?,
+            html: Self::decode_body(codec, body)?,
+        })
+    }
+}
+
+impl Cache for SqliteCache {
+    fn init(&self) -> Result<(), CacheError> {
+        if !self.config.enabled {
+            return Ok(());
+        }
+
+        fs::create_dir_all(&self.config.root_dir)?;
+        let conn = self.connect()?;
+        conn.execute_batch(SCHEMA)?;
+
+        let found: Option<String> = conn
+            .query_row("SELECT value FROM meta WHERE key = 'version'", [], |row| {
+                row.get(0)
+            })
+            .optional()?;
+        match found {
+            Some(found) if found != CACHE_VERSION.to_string() => {
+                Err(CacheError::VersionMismatch {
+                    expected: CACHE_VERSION,
+                    found,
+                })
+            }
+            Some(_) => Ok(()),
+            None => {
+                conn.execute(
+                    "INSERT INTO meta (key, value) VALUES ('version', ?1)",
+                    params![CACHE_VERSION.to_string()],
+                )?;
+                Ok(())
+            }
+        }
+    }
+
+    fn key_for_final_url(&self, final_url: &str) -> Result<CacheKey, CacheError> {
+        CacheKey::with_policy(final_url, &self.config.normalization)
+    }
+
+    fn load(&self, key: &CacheKey) -> Result<Option<CachedPage>, CacheError> {
+        if !self.config.enabled || !self.db_path().exists() {
+            return Ok(None);
+        }
+
+        let conn = self.connect()?;
+        let row: Option<(String, String, Vec<u8>, String)> = conn
+            .query_row(
+                "SELECT fetch_json, headers_json, body, body_codec
+                 FROM pages WHERE hash = ?1",
+                params![key.hash],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .optional()?;
+
+        row.map(|(fetch_json, headers_json, body, codec)| {
+            Self::decode_page(&fetch_json, &headers_json, &body, &codec)
+        })
+        .transpose()
+    }
+
+    fn snapshots(&self, key: &CacheKey) -> Result<Vec<CachedPage>, CacheError> {
+        let Some(current) = self.load(key)? else {
+            return Ok(Vec::new());
+        };
+
+        let conn = self.connect()?;
+        let mut stmt = conn.prepare(
+            "SELECT fetch_json, headers_json, body, body_codec FROM history
+             WHERE hash = ?1 ORDER BY fetched_at DESC, id DESC",
+        )?;
+        let rows = stmt.query_map(params![key.hash], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Vec<u8>>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })?;
+
+        let mut snapshots = vec![current];
+        for row in rows {
+            let (fetch_json, headers_json, body, codec) = row?;
+            snapshots.push(Self::decode_page(
+                &fetch_json,
+                &headers_json,
+                &body,
+                &codec,
+            )?);
+        }
+        Ok(snapshots)
+    }
+
+    fn store(&self, mut page: CachedPage) -> Result<CacheKey, CacheError> {
+        let key = self.key_for_final_url(&page.fetch.final_url)?;
+        if !self.config.enabled {
+            return Ok(key);
+        }
+        page.fetch.normalized_final_url = key.normalized_final_url.clone();
+        let codec = self.config.compression;
+        let body = codec.encode(page.html.as_bytes())?;
+        let domain = page.fetch.domain();
+
+        let mut conn = self.connect()?;
+        let tx = conn.transaction()?;
+        if self.config.history_limit > 0 {
+            tx.execute(
+                "INSERT INTO history (
+                    hash, fetched_at, fetch_json, headers_json, body, body_codec
+                 )
+                 SELECT hash, fetched_at, fetch_json, headers_json, body, body_codec
+                 FROM pages WHERE hash = ?1",
+                params![key.hash],
+            )?;
+            tx.execute(
+                "DELETE FROM history WHERE hash = ?1 AND id NOT IN (
+                    SELECT id FROM history WHERE hash = ?1
+                    ORDER BY fetched_at DESC, id DESC LIMIT ?2
+                 )",
+                params![key.hash, self.config.history_limit as i64],
+            )?;
+        }
+        tx.execute(
+            "INSERT OR REPLACE INTO pages (
+                hash, normalized_final_url, input_url, final_url, domain,
+                status, fetched_at, fetch_json, headers_json, body, body_codec
+             ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                key.hash,
+                key.normalized_final_url,
+                page.fetch.input_url,
+                page.fetch.final_url,
+                domain,
+                i64::from(page.fetch.status),
+                page.fetch.fetched_at_secs() as i64,
+                serde_json::to_string(&page.fetch)?,
+                serde_json::to_string(&page.headers)?,
+                body,
+                codec.as_str(),
+            ],
+        )?;
+        tx.execute("DELETE FROM failures WHERE hash = ?1", params![key.hash])?;
+        tx.commit()?;
+        Ok(key)
+    }
+
+    fn delete(&self, key: &CacheKey) -> Result<(), CacheError> {
+        if !self.db_path().exists() {
+            return Ok(());
+        }
+        let conn = self.connect()?;
+        conn.execute("DELETE FROM pages WHERE hash = ?1", params![key.hash])?;
+        conn.execute("DELETE FROM history WHERE hash = ?1", params![key.hash])?;
+        conn.execute("DELETE FROM failures WHERE hash = ?1", params![key.hash])?;
+        Ok(())
+    }
+
+    fn list(&self, query: &CacheQuery) -> Result<Vec<CachedFetch>, CacheError> {
+        if !self.db_path().exists() {
+            return Ok(Vec::new());
+        }
+
+        let conn = self.connect()?;
+        let mut stmt = conn.prepare(
+            "SELECT fetch_json FROM pages
+             WHERE (?1 IS NULL OR domain = ?1)
+               AND (?2 IS NULL OR status = ?2)
+             ORDER BY fetched_at DESC, final_url ASC
+             LIMIT ?3",
+        )?;
+        let domain = query.domain.as_deref().map(registered_domain);
+        let status = query.status.map(i64::from);
+        let limit = query.limit.map(|l| l as i64).unwrap_or(-1);
+
+        let rows = stmt.query_map(params![domain, status, limit], |row| {
+            row.get::<_, String>(0)
+        })?;
+        let mut entries = Vec::new();
+        for json in rows {
+            entries.push(serde_json::from_str(&json?)?);
+        }
+        Ok(entries)
+    }
+
+    fn stats(&self) -> Result<CacheStats, CacheError> {
+        let mut stats = CacheStats::default();
+        if !self.db_path().exists() {
+            return Ok(stats);
+        }
+
+        let conn = self.connect()?;
+        for table in ["pages", "history"] {
+            let mut stmt =
+                conn.prepare(&format!("SELECT body, body_codec FROM {table}"))?;
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get::<_, Vec<u8>>(0)?, row.get::<_, String>(1)?))
+            })?;
+            for row in rows {
+                let (body, codec) = row?;
+                if table == "pages" {
+                    stats.entries += 1;
+                } else {
+                    stats.snapshots += 1;
+                }
+                stats.stored_bytes += body.len() as u64;
+                stats.body_bytes += Self::decode_body(&codec, &body)?.len() as u64;
+                *stats.by_compression.entry(codec).or_insert(0) += 1;
+            }
+        }
+        Ok(stats)
+    }
+
+    fn migrate(&self, compression: Compression) -> Result<usize, CacheError> {
+        if !self.db_path().exists() {
+            return Ok(0);
+        }
+
+        let mut conn = self.connect()?;
+        let tx = conn.transaction()?;
+        let mut migrated = 0;
+        for table in ["pages", "history"] {
+            let rows: Vec<(i64, Vec<u8>, String)> = {
+                let mut stmt = tx.prepare(&format!(
+                    "SELECT rowid, body, body_codec FROM {table} \
+                     WHERE body_codec != ?1"
+                ))?;
+                let rows = stmt
+                    .query_map(params![compression.as_str()], |row| {
+                        Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+                    })?;
+                rows.collect::<Result<_, _>>()?
+            };
+
+            for (row_id, body, codec) in &rows {
+                let html = Self::decode_body(codec, body)?;
+                tx.execute(
+                    &format!(
+                        "UPDATE {table} SET body = ?1, body_codec = ?2 \
+                         WHERE rowid = ?3"
+                    ),
+                    params![
+                        compression.encode(html.as_bytes())?,
+                        compression.as_str(),
+                        row_id
+                    ],
+                )?;
+            }
+            migrated += rows.len();
+        }
+        tx.commit()?;
+        Ok(migrated)
+    }
+
+    fn store_failure(
+        &self,
+        key: &CacheKey,
+        failure: &CachedFailure,
+    ) -> Result<(), CacheError> {
+        if !self.config.enabled || self.config.failure_ttl_secs == 0 {
+            return Ok(());
+        }
+        self.connect()?.execute(
+            "INSERT OR REPLACE INTO failures (hash, failed_at, failure_json)
+             VALUES (?1, ?2, ?3)",
+            params![
+                key.hash,
+                failure.failed_at_secs() as i64,
+                serde_json::to_string(failure)?,
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn load_failure(
+        &self,
+        key: &CacheKey,
+    ) -> Result<Option<CachedFailure>, CacheError> {
+        if !self.config.enabled
+            || self.config.failure_ttl_secs == 0
+            || !self.db_path().exists()
+        {
+            return Ok(None);
+        }
+        let json: Option<String> = self
+            .connect()?
+            .query_row(
+                "SELECT failure_json FROM failures WHERE hash = ?1",
+                params![key.hash],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let Some(json) = json else {
+            return Ok(None);
+        };
+        let failure: CachedFailure = serde_json::from_str(&json)?;
+        Ok(failure
+            .is_fresh(self.config.failure_ttl_secs, now_secs())
+            .then_some(failure))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::*;
+
+    fn temp_root() -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        std::env::temp_dir().join(format!("pageinfo-sqlite-test-{nanos}"))
+    }
+
+    fn make_cached_page(
+        final_url: &str,
+        status: u16,
+        fetched_at: &str,
+    ) -> CachedPage {
+        let key = CacheKey::new(final_url).unwrap();
+        CachedPage {
+            fetch: CachedFetch {
+                input_url: final_url.to_string(),
+                final_url: final_url.to_string(),
+                normalized_final_url: key.normalized_final_url,
+                status,
+                fetched_at: fetched_at.to_string(),
+                ..Default::default()
+            },
+            headers: HashMap::new(),
+            html: "<html></html>".to_string(),
+        }
+    }
+
+    #[test]
+    fn round_trip_store_and_load() {
+        let root_dir = temp_root();
+        let cache = SqliteCache::new(CacheConfig {
+            root_dir: root_dir.clone(),
+            compression: Compression::Zstd,
+            ..Default::default()
+        });
+        cache.init().unwrap();
+
+        let key = cache
+            .store(make_cached_page("https://example.com/news", 200, "10"))
+            .unwrap();
+        let loaded = cache.load(&key).unwrap().unwrap();
+        assert_eq!(loaded.fetch.final_url, "https://example.com/news");
+        assert_eq!(loaded.html, "<html></html>");
+
+        cache.delete(&key).unwrap();
+        assert!(cache.load(&key).unwrap().is_none());
+
+        fs::remove_dir_all(root_dir).unwrap();
+    }
+
+    #[test]
+    fn disabled_cache_stores_nothing() {
+        let root_dir = temp_root();
+        let cache = SqliteCache::new(CacheConfig {
+            root_dir: root_dir.clone(),
+            enabled: false,
+            ..Default::default()
+        });
+        cache.init().unwrap();
+
+        cache
+            .store(make_cached_page("https://example.com/news", 200, "10"))
+            .unwrap();
+        assert!(!root_dir.exists());
+    }
+
+    #[test]
+    fn list_filters_by_domain_and_status_newest_first() {
+        let root_dir = temp_root();
+        let cache = SqliteCache::new(CacheConfig {
+            root_dir: root_dir.clone(),
+            ..Default::default()
+        });
+        cache.init().unwrap();
+
+        cache
+            .store(make_cached_page("https://www.example.com/a", 200, "10"))
+            .unwrap();
+        cache
+            .store(make_cached_page("https://blog.example.com/b", 200, "20"))
+            .unwrap();
+        cache
+            .store(make_cached_page("https://example.com/gone", 404, "30"))
+            .unwrap();
+        cache
+            .store(make_cached_page("https://other.com/c", 200, "40"))
+            .unwrap();
+
+        let query = CacheQuery {
+            domain: Some("example.com".to_string()),
+            status: Some(200),
+            limit: None,
+        };
+        let urls: Vec<String> = cache
+            .list(&query)
+            .unwrap()
+            .into_iter()
+            .map(|f| f.final_url)
+            .collect();
+        assert_eq!(
+            urls,
+            vec!["https://blog.example.com/b", "https://www.example.com/a"]
+        );
+
+        let limited = cache
+            .list(&CacheQuery {
+                limit: Some(1),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(limited[0].final_url, "https://other.com/c");
+
+        fs::remove_dir_all(root_dir).unwrap();
+    }
+
+    #[test]
+    fn store_moves_previous_row_into_history() {
+        let root_dir = temp_root();
+        let cache = SqliteCache::new(CacheConfig {
+            root_dir: root_dir.clone(),
+            history_limit: 1,
+            ..Default::default()
+        });
+        cache.init().unwrap();
+
+        for fetched_at in ["10", "20", "30"] {
+            cache
+                .store(make_cached_page("https://example.com/a", 200, fetched_at))
+                .unwrap();
+        }
+
+        let key = cache.key_for_final_url("https://example.com/a").unwrap();
+        let fetched: Vec<String> = cache
+            .snapshots(&key)
+            .unwrap()
+            .into_iter()
+            .map(|page| page.fetch.fetched_at)
+            .collect();
+        assert_eq!(fetched, vec!["30", "20"]);
+
+        fs::remove_dir_all(root_dir).unwrap();
+    }
+
+    #[test]
+    fn migrate_recompresses_rows() {
+        let root_dir = temp_root();
+        let cache = SqliteCache::new(CacheConfig {
+            root_dir: root_dir.clone(),
+            ..Default::default()
+        });
+        cache.init().unwrap();
+        cache
+            .store(make_cached_page("https://example.com/a", 200, "10"))
+            .unwrap();
+
+        cache
+            .store(make_cached_page("https://example.com/a", 200, "20"))
+            .unwrap();
+
+        assert_eq!(cache.migrate(Compression::Gzip).unwrap(), 2);
+        let stats = cache.stats().unwrap();
+        assert_eq!((stats.entries, stats.snapshots), (1, 1));
+        assert_eq!(stats.by_compression.get("gzip"), Some(&2));
+
+        let key = cache.key_for_final_url("https://example.com/a").unwrap();
+        assert_eq!(cache.load(&key).unwrap().unwrap().html, "<html></html>");
+
+        fs::remove_dir_all(root_dir).unwrap();
+    }
+
+    #[test]
+    fn failures_round_trip_and_clear_on_store() {
+        let root_dir = temp_root();
+        let cache = SqliteCache::new(CacheConfig {
+            root_dir: root_dir.clone(),
+            ..Default::default()
+        });
+        cache.init().unwrap();
+
+        let url = "https://example.com/news";
+        let key = cache.key_for_final_url(url).unwrap();
+        let failure = CachedFailure {
+            url: url.to_string(),
+            kind: crate::cache::FailureKind::AllAttemptsFailed,
+            status: None,
+            reason: None,
+            attempts: 3,
+            failed_at: now_secs().to_string(),
+        };
+        cache.store_failure(&key, &failure).unwrap();
+        assert_eq!(cache.load_failure(&key).unwrap(), Some(failure));
+
+        cache.store(make_cached_page(url, 200, "10")).unwrap();
+        assert!(cache.load_failure(&key).unwrap().is_none());
+
+        fs::remove_dir_all(root_dir).unwrap();
+    }
+}
@@ -1,12 +1,30 @@
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+use fslock::LockFile;
+
+use crate::cache::compression::Compression;
 use crate::cache::error::CacheError;
 use crate::cache::key::CacheKey;
-use crate::cache::types::{CacheConfig, CachedFetch, CachedPage};
+use crate::cache::types::{
+    CacheBackend, CacheConfig, CacheQuery, CacheStats, CachedFailure, CachedFetch,
+    CachedPage,
+};
+
+pub(crate) const CACHE_VERSION: u32 = 1;
+
+/// Staging directories older than this are assumed to belong to a writer that
+/// crashed before renaming them into place.
+const STALE_TMP_AGE: Duration = Duration::from_secs(60 * 60);
 
-const CACHE_VERSION: u32 = 1;
+/// Written into every entry with a unique value. Readers compare it before
+/// and after reading, which tells them a writer swapped the entry mid-read.
+const GENERATION_FILE: &str = "generation";
+
+/// How often a reader retries an entry that kept changing under it.
+const READ_ATTEMPTS: usize = 3;
 
 pub trait Cache {
     fn init(&self) -> Result<(), CacheError>;
@@ -15,6 +33,43 @@ pub trait Cache {
     fn store(&self, page: CachedPage) -> Result<CacheKey, CacheError>;
     #[allow(dead_code)]
     fn delete(&self, key: &CacheKey) -> Result<(), CacheError>;
+    /// Returns the current entry followed by its older snapshots, newest
+    /// first.
+    fn snapshots(&self, key: &CacheKey) -> Result<Vec<CachedPage>, CacheError>;
+    /// Lists fetch metadata of stored entries, newest first.
+    fn list(&self, query: &CacheQuery) -> Result<Vec<CachedFetch>, CacheError>;
+    fn stats(&self) -> Result<CacheStats, CacheError>;
+    /// Rewrites stored page bodies, current entries and history snapshots
+    /// alike, with `compression`. Returns how many bodies changed.
+    fn migrate(&self, compression: Compression) -> Result<usize, CacheError>;
+    /// Records a failed fetch under `key`, replacing any earlier one. A later
+    /// successful `store` clears it.
+    fn store_failure(
+        &self,
+        key: &CacheKey,
+        failure: &CachedFailure,
+    ) -> Result<(), CacheError>;
+    /// The failure recorded under `key`, if younger than
+    /// `CacheConfig::failure_ttl_secs`.
+    fn load_failure(
+        &self,
+        key: &CacheKey,
+    ) -> Result<Option<CachedFailure>, CacheError>;
+}
+
+/// Opens the backend selected by `config.backend`.
+pub fn open(config: CacheConfig) -> Result<Box<dyn Cache>, CacheError> {
+    match config.backend {
+        CacheBackend::File => Ok(Box::new(FileCache::new(config))),
+        #[cfg(feature = "sqlite")]
+        CacheBackend::Sqlite => {
+            Ok(Box::new(crate::cache::sqlite::SqliteCache::new(config)))
+        }
+        #[cfg(not(feature = "sqlite"))]
+        CacheBackend::Sqlite => Err(CacheError::BackendUnavailable(
+            "sqlite (rebuild with `--features sqlite`)".to_string(),
+        )),
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -31,6 +86,7 @@ impl FileCache {
         self.config.enabled
     }
 
+    #[allow(dead_code)]
     pub fn should_refresh(&self) -> bool {
         self.config.refresh
     }
@@ -43,20 +99,386 @@ impl FileCache {
         self.config.root_dir.join("pages")
     }
 
+    fn locks_dir(&self) -> PathBuf {
+        self.config.root_dir.join("locks")
+    }
+
+    fn tmp_dir(&self) -> PathBuf {
+        self.config.root_dir.join("tmp")
+    }
+
+    fn history_dir(&self, key: &CacheKey) -> PathBuf {
+        self.config.root_dir.join("history").join(&key.hash)
+    }
+
     fn entry_dir(&self, key: &CacheKey) -> PathBuf {
         self.pages_dir().join(&key.hash)
     }
 
+    fn failure_path(&self, key: &CacheKey) -> PathBuf {
+        self.config
+            .root_dir
+            .join("failures")
+            .join(format!("{}.json", key.hash))
+    }
+
     fn fetch_path(&self, key: &CacheKey) -> PathBuf {
         self.entry_dir(key).join("fetch.json")
     }
 
-    fn headers_path(&self, key: &CacheKey) -> PathBuf {
-        self.entry_dir(key).join("headers.json")
+    /// Finds the page body stored in an entry or snapshot directory,
+    /// whichever codec it was written with.
+    fn find_body(dir: &Path) -> Option<(PathBuf, Compression)> {
+        Compression::ALL
+            .into_iter()
+            .map(|codec| (dir.join(codec.body_file_name()), codec))
+            .find(|(path, _)| path.exists())
+    }
+
+    fn decode_body(
+        bytes: &[u8],
+        compression: Compression,
+    ) -> Result<String, CacheError> {
+        let bytes = compression.decode(bytes)?;
+        String::from_utf8(bytes).map_err(|e| {
+            CacheError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+        })
+    }
+
+    /// Takes the cross-process advisory lock writers hold for one entry. The
+    /// lock is released when the returned file is dropped; readers never take
+    /// it.
+    fn lock_entry(&self, key: &CacheKey) -> Result<LockFile, CacheError> {
+        self.lock_hash(&key.hash)
+    }
+
+    fn lock_hash(&self, hash: &str) -> Result<LockFile, CacheError> {
+        let locks_dir = self.locks_dir();
+        fs::create_dir_all(&locks_dir)?;
+        let mut lock = LockFile::open(&locks_dir.join(format!("{hash}.lock")))?;
+        lock.lock()?;
+        Ok(lock)
+    }
+
+    /// Unique scratch path under `tmp/`, on the same filesystem as `pages/`
+    /// so the final rename is atomic.
+    fn scratch_path(&self, name: &str) -> PathBuf {
+        self.tmp_dir().join(format!("{name}.{}", unique_suffix()))
+    }
+
+    /// Reads a complete entry or snapshot directory without locking. `None`
+    /// when a file is missing or the directory was swapped while reading.
+    fn read_entry(
+        dir: &Path,
+    ) -> Result<Option<(CachedPage, Compression)>, CacheError> {
+        let generation = read_if_exists(&dir.join(GENERATION_FILE))?;
+        let Some((body_path, compression)) = Self::find_body(dir) else {
+            return Ok(None);
+        };
+        let (Some(fetch), Some(headers), Some(body)) = (
+            read_if_exists(&dir.join("fetch.json"))?,
+            read_if_exists(&dir.join("headers.json"))?,
+            read_if_exists(&body_path)?,
+        ) else {
+            return Ok(None);
+        };
+        if read_if_exists(&dir.join(GENERATION_FILE))? != generation {
+            return Ok(None);
+        }
+
+        let fetch: CachedFetch = serde_json::from_slice(&fetch)?;
+        let headers: HashMap<String, String> = serde_json::from_slice(&headers)?;
+        let html = Self::decode_body(&body, compression)?;
+
+        Ok(Some((
+            CachedPage {
+                fetch,
+                headers,
+                html,
+            },
+            compression,
+        )))
+    }
+
+    /// Reads the current entry, retrying when a writer swaps it mid-read. A
+    /// writer that crashed between its two renames leaves no `pages/` entry;
+    /// the replaced one is then still the newest snapshot or a retired
+    /// directory in `tmp/` and is served from there. Returns the directory
+    /// read.
+    fn read_current(
+        &self,
+        key: &CacheKey,
+    ) -> Result<Option<(CachedPage, PathBuf)>, CacheError> {
+        for _ in 0..READ_ATTEMPTS {
+            let entry_dir = self.entry_dir(key);
+            let dir = if entry_dir.exists() {
+                entry_dir
+            } else {
+                let fallback = match self.history_snapshots(key)?.into_iter().next()
+                {
+                    Some(snapshot) => Some(snapshot),
+                    None => self.retired_dirs(key)?.pop(),
+                };
+                let Some(dir) = fallback else {
+                    return Ok(None);
+                };
+                dir
+            };
+            if let Some((page, _)) = Self::read_entry(&dir)? {
+                return Ok(Some((page, dir)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Entries moved aside by writers replacing them without history.
+    fn retired_dirs(&self, key: &CacheKey) -> Result<Vec<PathBuf>, CacheError> {
+        let tmp_dir = self.tmp_dir();
+        if !tmp_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let prefix = format!("{}.old.", key.hash);
+        let mut retired = Vec::new();
+        for entry in fs::read_dir(tmp_dir)? {
+            let entry = entry?;
+            if entry.file_name().to_string_lossy().starts_with(&prefix) {
+                retired.push(entry.path());
+            }
+        }
+        retired.sort();
+        Ok(retired)
+    }
+
+    /// Writes every file of an entry into a staging directory, then swaps it
+    /// into `pages/` with renames so readers never see a partial entry.
+    /// With `keep_previous`, the replaced entry moves to `history/`. Callers
+    /// hold the entry lock.
+    fn write_entry(
+        &self,
+        key: &CacheKey,
+        page: &CachedPage,
+        compression: Compression,
+        keep_previous: bool,
+    ) -> Result<(), CacheError> {
+        let staging = self.stage(key, page, compression)?;
+        let swapped = self.swap_in(key, &staging, keep_previous);
+        if swapped.is_err() {
+            let _ = fs::remove_dir_all(&staging);
+        }
+        swapped
+    }
+
+    /// Writes every file of an entry into a new staging directory under
+    /// `tmp/` and returns its path.
+    fn stage(
+        &self,
+        key: &CacheKey,
+        page: &CachedPage,
+        compression: Compression,
+    ) -> Result<PathBuf, CacheError> {
+        let staging = self.scratch_path(&key.hash);
+        fs::create_dir_all(&staging)?;
+
+        let written = (|| {
+            fs::write(staging.join(GENERATION_FILE), unique_suffix())?;
+            self.write_json(&staging.join("fetch.json"), &page.fetch)?;
+            self.write_json(&staging.join("headers.json"), &page.headers)?;
+            fs::write(
+                staging.join(compression.body_file_name()),
+                compression.encode(page.html.as_bytes())?,
+            )?;
+            Ok(())
+        })();
+        match written {
+            Ok(()) => Ok(staging),
+            Err(e) => {
+                let _ = fs::remove_dir_all(&staging);
+                Err(e)
+            }
+        }
+    }
+
+    /// Rewrites one history snapshot with `compression`. The copy is renamed
+    /// in under the same fetch-time prefix, so the order of snapshots holds,
+    /// before the old one is removed. Callers hold the entry lock.
+    fn migrate_snapshot(
+        &self,
+        key: &CacheKey,
+        snapshot: &Path,
+        compression: Compression,
+    ) -> Result<bool, CacheError> {
+        let Some((page, codec)) = Self::read_entry(snapshot)? else {
+            return Ok(false);
+        };
+        if codec == compression {
+            return Ok(false);
+        }
+        let name = snapshot
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let prefix = name.split_once('.').map_or(name.as_str(), |(p, _)| p);
+        let staging = self.stage(key, &page, compression)?;
+        let renamed = self
+            .history_dir(key)
+            .join(format!("{prefix}.{}", unique_suffix()));
+        if let Err(e) = fs::rename(&staging, renamed) {
+            let _ = fs::remove_dir_all(&staging);
+            return Err(e.into());
+        }
+        fs::remove_dir_all(snapshot)?;
+        Ok(true)
+    }
+
+    fn swap_in(
+        &self,
+        key: &CacheKey,
+        staging: &Path,
+        keep_previous: bool,
+    ) -> Result<(), CacheError> {
+        let entry_dir = self.entry_dir(key);
+        if !entry_dir.exists() {
+            fs::rename(staging, &entry_dir)?;
+            return Ok(());
+        }
+
+        if keep_previous && self.config.history_limit > 0 {
+            let fetched_at = Self::read_fetch(&entry_dir.join("fetch.json"))?
+                .map(|fetch| fetch.fetched_at_secs())
+                .unwrap_or(0);
+            let history_dir = self.history_dir(key);
+            fs::create_dir_all(&history_dir)?;
+            let snapshot =
+                history_dir.join(format!("{fetched_at:020}.{}", unique_suffix()));
+            fs::rename(&entry_dir, snapshot)?;
+            fs::rename(staging, &entry_dir)?;
+            return self.prune_history(key);
+        }
+
+        let retired = self.scratch_path(&format!("{}.old", key.hash));
+        fs::rename(&entry_dir, &retired)?;
+        fs::rename(staging, &entry_dir)?;
+        fs::remove_dir_all(retired)?;
+        Ok(())
+    }
+
+    /// Snapshot directories of an entry, newest first. Names start with the
+    /// zero-padded fetch time, so they sort chronologically.
+    fn history_snapshots(
+        &self,
+        key: &CacheKey,
+    ) -> Result<Vec<PathBuf>, CacheError> {
+        let history_dir = self.history_dir(key);
+        if !history_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut snapshots = fs::read_dir(history_dir)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+        snapshots.sort();
+        snapshots.reverse();
+        Ok(snapshots)
+    }
+
+    fn prune_history(&self, key: &CacheKey) -> Result<(), CacheError> {
+        for stale in self
+            .history_snapshots(key)?
+            .into_iter()
+            .skip(self.config.history_limit)
+        {
+            fs::remove_dir_all(stale)?;
+        }
+        Ok(())
     }
 
-    fn html_path(&self, key: &CacheKey) -> PathBuf {
-        self.entry_dir(key).join("page.html")
+    /// Removes staging directories and scratch files left behind by crashed
+    /// writers and puts back entries they retired without swapping a
+    /// replacement in.
+    fn sweep_tmp(&self) -> Result<(), CacheError> {
+        let tmp_dir = self.tmp_dir();
+        if !tmp_dir.exists() {
+            return Ok(());
+        }
+
+        for entry in fs::read_dir(tmp_dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if let Some((hash, _)) = name.split_once(".old.") {
+                self.restore_retired(hash, &entry.path())?;
+                continue;
+            }
+            let stale = entry
+                .metadata()?
+                .modified()?
+                .elapsed()
+                .is_ok_and(|age| age > STALE_TMP_AGE);
+            if stale {
+                let _ = if entry.file_type()?.is_dir() {
+                    fs::remove_dir_all(entry.path())
+                } else {
+                    fs::remove_file(entry.path())
+                };
+            }
+        }
+        Ok(())
+    }
+
+    /// Moves a retired entry back into `pages/` unless a newer one is there.
+    /// Under the entry lock no writer is between its renames, so a retired
+    /// directory without a `pages/` entry means its writer crashed.
+    fn restore_retired(
+        &self,
+        hash: &str,
+        retired: &Path,
+    ) -> Result<(), CacheError> {
+        let _lock = self.lock_hash(hash)?;
+        if !retired.exists() {
+            return Ok(());
+        }
+        let entry_dir = self.pages_dir().join(hash);
+        if entry_dir.exists() {
+            let _ = fs::remove_dir_all(retired);
+        } else {
+            fs::rename(retired, entry_dir)?;
+        }
+        Ok(())
+    }
+
+    fn keys(&self) -> Result<Vec<CacheKey>, CacheError> {
+        let pages_dir = self.pages_dir();
+        if !pages_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut keys = Vec::new();
+        for entry in fs::read_dir(pages_dir)? {
+            let entry = entry?;
+            if !entry.file_type()?.is_dir() {
+                continue;
+            }
+            let hash = entry.file_name().to_string_lossy().to_string();
+            let Some(fetch) = Self::read_fetch(&entry.path().join("fetch.json"))?
+            else {
+                continue;
+            };
+            keys.push(CacheKey {
+                normalized_final_url: fetch.normalized_final_url,
+                hash,
+            });
+        }
+        keys.sort_by(|a, b| a.hash.cmp(&b.hash));
+        Ok(keys)
+    }
+
+    /// Reads `fetch.json` without the entry lock. An entry being swapped out
+    /// by another process reads as missing.
+    fn read_fetch(path: &Path) -> Result<Option<CachedFetch>, CacheError> {
+        read_if_exists(path)?
+            .map(|bytes| serde_json::from_slice(&bytes))
+            .transpose()
+            .map_err(Into::into)
     }
 
     fn read_version(&self) -> Result<Option<String>, CacheError> {
@@ -78,6 +500,38 @@ impl FileCache {
     }
 }
 
+/// Reads a file, treating one removed by a concurrent swap as absent.
+fn read_if_exists(path: &Path) -> Result<Option<Vec<u8>>, CacheError> {
+    match fs::read(path) {
+        Ok(bytes) => Ok(Some(bytes)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn remove_if_exists(path: &Path) -> Result<(), CacheError> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+pub(crate) fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// `<pid>.<nanos>`, unique enough to keep concurrent writers apart.
+fn unique_suffix() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    format!("{}.{nanos}", std::process::id())
+}
+
 impl Cache for FileCache {
     fn init(&self) -> Result<(), CacheError> {
         if !self.is_enabled() {
@@ -85,6 +539,8 @@ impl Cache for FileCache {
         }
 
         fs::create_dir_all(self.pages_dir())?;
+        fs::create_dir_all(self.tmp_dir())?;
+        self.sweep_tmp()?;
 
         match self.read_version()? {
             Some(found) if found != CACHE_VERSION.to_string() => {
@@ -95,69 +551,193 @@ impl Cache for FileCache {
             }
             Some(_) => Ok(()),
             None => {
-                fs::write(self.version_path(), CACHE_VERSION.to_string())?;
+                let scratch = self.scratch_path("VERSION");
+                fs::write(&scratch, CACHE_VERSION.to_string())?;
+                fs::rename(scratch, self.version_path())?;
                 Ok(())
             }
         }
     }
 
     fn key_for_final_url(&self, final_url: &str) -> Result<CacheKey, CacheError> {
-        CacheKey::new(final_url)
+        CacheKey::with_policy(final_url, &self.config.normalization)
     }
 
     fn load(&self, key: &CacheKey) -> Result<Option<CachedPage>, CacheError> {
-        if !self.is_enabled() {
-            return Ok(None);
-        }
-
-        let entry_dir = self.entry_dir(key);
-        if !entry_dir.exists() {
+        if !self.is_enabled() || !self.pages_dir().exists() {
             return Ok(None);
         }
 
-        let fetch_path = self.fetch_path(key);
-        let headers_path = self.headers_path(key);
-        let html_path = self.html_path(key);
+        Ok(self.read_current(key)?.map(|(page, _)| page))
+    }
 
-        if !fetch_path.exists() || !headers_path.exists() || !html_path.exists() {
-            return Ok(None);
+    fn snapshots(&self, key: &CacheKey) -> Result<Vec<CachedPage>, CacheError> {
+        if !self.is_enabled() || !self.pages_dir().exists() {
+            return Ok(Vec::new());
         }
 
-        let fetch: CachedFetch = serde_json::from_slice(&fs::read(fetch_path)?)?;
-        let headers: HashMap<String, String> =
-            serde_json::from_slice(&fs::read(headers_path)?)?;
-        let html = fs::read_to_string(html_path)?;
-
-        Ok(Some(CachedPage {
-            fetch,
-            headers,
-            html,
-        }))
+        let Some((current, current_dir)) = self.read_current(key)? else {
+            return Ok(Vec::new());
+        };
+        let mut snapshots = vec![current];
+        for dir in self.history_snapshots(key)? {
+            if dir == current_dir {
+                continue;
+            }
+            if let Some((page, _)) = Self::read_entry(&dir)? {
+                snapshots.push(page);
+            }
+        }
+        Ok(snapshots)
     }
 
-    fn store(&self, page: CachedPage) -> Result<CacheKey, CacheError> {
+    fn store(&self, mut page: CachedPage) -> Result<CacheKey, CacheError> {
         let key = self.key_for_final_url(&page.fetch.final_url)?;
-        let entry_dir = self.entry_dir(&key);
-        fs::create_dir_all(&entry_dir)?;
-        self.write_json(&self.fetch_path(&key), &page.fetch)?;
-        self.write_json(&self.headers_path(&key), &page.headers)?;
-        fs::write(self.html_path(&key), &page.html)?;
+        if !self.is_enabled() {
+            return Ok(key);
+        }
+        page.fetch.normalized_final_url = key.normalized_final_url.clone();
+        let _lock = self.lock_entry(&key)?;
+        self.write_entry(&key, &page, self.config.compression, true)?;
+        remove_if_exists(&self.failure_path(&key))?;
         Ok(key)
     }
 
     fn delete(&self, key: &CacheKey) -> Result<(), CacheError> {
+        remove_if_exists(&self.failure_path(key))?;
         let entry_dir = self.entry_dir(key);
+        let history_dir = self.history_dir(key);
+        if !entry_dir.exists() && !history_dir.exists() {
+            return Ok(());
+        }
+
+        let _lock = self.lock_entry(key)?;
+        // History goes first so readers never fall back to a snapshot of a
+        // deleted entry.
+        if history_dir.exists() {
+            fs::remove_dir_all(history_dir)?;
+        }
         if entry_dir.exists() {
-            fs::remove_dir_all(entry_dir)?;
+            fs::create_dir_all(self.tmp_dir())?;
+            // Not `.old.`: a crash here must not restore the entry.
+            let deleted = self.scratch_path(&format!("{}.deleted", key.hash));
+            fs::rename(entry_dir, &deleted)?;
+            fs::remove_dir_all(deleted)?;
         }
         Ok(())
     }
+
+    fn list(&self, query: &CacheQuery) -> Result<Vec<CachedFetch>, CacheError> {
+        let mut entries = Vec::new();
+        for key in self.keys()? {
+            let Some(fetch) = Self::read_fetch(&self.fetch_path(&key))? else {
+                continue;
+            };
+            if query.matches(&fetch) {
+                entries.push(fetch);
+            }
+        }
+        entries.sort_by(|a, b| {
+            b.fetched_at_secs()
+                .cmp(&a.fetched_at_secs())
+                .then_with(|| a.final_url.cmp(&b.final_url))
+        });
+        if let Some(limit) = query.limit {
+            entries.truncate(limit);
+        }
+        Ok(entries)
+    }
+
+    fn stats(&self) -> Result<CacheStats, CacheError> {
+        let mut stats = CacheStats::default();
+        for key in self.keys()? {
+            let dirs = std::iter::once(self.entry_dir(&key))
+                .chain(self.history_snapshots(&key)?);
+            for (index, dir) in dirs.enumerate() {
+                let Some((path, codec)) = Self::find_body(&dir) else {
+                    continue;
+                };
+                let Some(bytes) = read_if_exists(&path)? else {
+                    continue;
+                };
+                if index == 0 {
+                    stats.entries += 1;
+                } else {
+                    stats.snapshots += 1;
+                }
+                stats.stored_bytes += bytes.len() as u64;
+                stats.body_bytes += Self::decode_body(&bytes, codec)?.len() as u64;
+                *stats
+                    .by_compression
+                    .entry(codec.as_str().to_string())
+                    .or_insert(0) += 1;
+            }
+        }
+        Ok(stats)
+    }
+
+    fn migrate(&self, compression: Compression) -> Result<usize, CacheError> {
+        let mut migrated = 0;
+        for key in self.keys()? {
+            let _lock = self.lock_entry(&key)?;
+            for snapshot in self.history_snapshots(&key)? {
+                if self.migrate_snapshot(&key, &snapshot, compression)? {
+                    migrated += 1;
+                }
+            }
+            let Some((page, codec)) = Self::read_entry(&self.entry_dir(&key))?
+            else {
+                continue;
+            };
+            if codec == compression {
+                continue;
+            }
+            self.write_entry(&key, &page, compression, false)?;
+            migrated += 1;
+        }
+        Ok(migrated)
+    }
+
+    fn store_failure(
+        &self,
+        key: &CacheKey,
+        failure: &CachedFailure,
+    ) -> Result<(), CacheError> {
+        if !self.is_enabled() || self.config.failure_ttl_secs == 0 {
+            return Ok(());
+        }
+        let path = self.failure_path(key);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::create_dir_all(self.tmp_dir())?;
+        let scratch = self.scratch_path(&format!("{}.failure", key.hash));
+        self.write_json(&scratch, failure)?;
+        fs::rename(scratch, path)?;
+        Ok(())
+    }
+
+    fn load_failure(
+        &self,
+        key: &CacheKey,
+    ) -> Result<Option<CachedFailure>, CacheError> {
+        if !self.is_enabled() || self.config.failure_ttl_secs == 0 {
+            return Ok(None);
+        }
+        let failure: CachedFailure = match fs::read(self.failure_path(key)) {
+            Ok(bytes) => serde_json::from_slice(&bytes)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(failure
+            .is_fresh(self.config.failure_ttl_secs, now_secs())
+            .then_some(failure))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
-    use std::time::{SystemTime, UNIX_EPOCH};
 
     use super::*;
 
@@ -184,6 +764,7 @@ mod tests {
                 normalized_final_url: key.normalized_final_url,
                 status,
                 fetched_at: "0".to_string(),
+                ..Default::default()
             },
             headers,
             html: html.to_string(),
@@ -197,6 +778,7 @@ mod tests {
             root_dir: root_dir.clone(),
             enabled: true,
             refresh: false,
+            ..Default::default()
         });
 
         cache.init().unwrap();
@@ -222,6 +804,42 @@ mod tests {
         fs::remove_dir_all(root_dir).unwrap();
     }
 
+    #[test]
+    fn load_accepts_fetch_json_without_transparency_fields() {
+        let root_dir = temp_root();
+        let cache = FileCache::new(CacheConfig {
+            root_dir: root_dir.clone(),
+            enabled: true,
+            refresh: false,
+            ..Default::default()
+        });
+
+        cache.init().unwrap();
+
+        let key = cache.key_for_final_url("https://example.com/old").unwrap();
+        fs::create_dir_all(cache.entry_dir(&key)).unwrap();
+        fs::write(
+            cache.fetch_path(&key),
+            r#"{"input_url":"https://example.com/old","final_url":"https://example.com/old","normalized_final_url":"https://example.com/old","status":200,"fetched_at":"0"}"#,
+        )
+        .unwrap();
+        fs::write(cache.entry_dir(&key).join("headers.json"), "{}").unwrap();
+        fs::write(
+            cache
+                .entry_dir(&key)
+                .join(Compression::None.body_file_name()),
+            "<html></html>",
+        )
+        .unwrap();
+
+        let loaded = cache.load(&key).unwrap().unwrap();
+        assert_eq!(loaded.fetch.duration_ms, 0);
+        assert_eq!(loaded.fetch.attempts, 0);
+        assert!(loaded.fetch.emulation_used.is_none());
+
+        fs::remove_dir_all(root_dir).unwrap();
+    }
+
     #[test]
     fn delete_removes_cached_entry() {
         let root_dir = temp_root();
@@ -229,6 +847,7 @@ mod tests {
             root_dir: root_dir.clone(),
             enabled: true,
             refresh: false,
+            ..Default::default()
         });
 
         cache.init().unwrap();
@@ -250,6 +869,415 @@ mod tests {
         fs::remove_dir_all(root_dir).unwrap();
     }
 
+    #[test]
+    fn compressed_entries_load_transparently() {
+        let root_dir = temp_root();
+        for codec in [Compression::Gzip, Compression::Zstd] {
+            let cache = FileCache::new(CacheConfig {
+                root_dir: root_dir.clone(),
+                compression: codec,
+                ..Default::default()
+            });
+            cache.init().unwrap();
+
+            let page = make_cached_page(
+                "https://example.com",
+                "https://example.com/news",
+                200,
+                HashMap::new(),
+                "<html>compressed</html>",
+            );
+            let key = cache.store(page).unwrap();
+
+            assert!(cache.entry_dir(&key).join(codec.body_file_name()).exists());
+            assert!(
+                !cache
+                    .entry_dir(&key)
+                    .join(Compression::None.body_file_name())
+                    .exists()
+            );
+            let loaded = cache.load(&key).unwrap().unwrap();
+            assert_eq!(loaded.html, "<html>compressed</html>");
+        }
+
+        fs::remove_dir_all(root_dir).unwrap();
+    }
+
+    #[test]
+    fn migrate_recompresses_existing_entries_and_reports_savings() {
+        let root_dir = temp_root();
+        let cache = FileCache::new(CacheConfig {
+            root_dir: root_dir.clone(),
+            ..Default::default()
+        });
+        cache.init().unwrap();
+
+        let html = "<li class=\"story\">headline</li>".repeat(200);
+        for path in ["a", "b"] {
+            let url = format!("https://example.com/{path}");
+            cache
+                .store(make_cached_page(&url, &url, 200, HashMap::new(), &html))
+                .unwrap();
+        }
+
+        let before = cache.stats().unwrap();
+        assert_eq!(before.entries, 2);
+        assert_eq!(before.saved_bytes(), 0);
+        assert_eq!(before.by_compression.get("none"), Some(&2));
+
+        assert_eq!(cache.migrate(Compression::Zstd).unwrap(), 2);
+        assert_eq!(cache.migrate(Compression::Zstd).unwrap(), 0);
+
+        let after = cache.stats().unwrap();
+        assert_eq!(after.entries, 2);
+        assert_eq!(after.body_bytes, before.body_bytes);
+        assert!(after.saved_bytes() > 0);
+        assert_eq!(after.by_compression.get("zstd"), Some(&2));
+
+        let key = cache.key_for_final_url("https://example.com/a").unwrap();
+        assert_eq!(cache.load(&key).unwrap().unwrap().html, html);
+
+        fs::remove_dir_all(root_dir).unwrap();
+    }
+
+    #[test]
+    fn list_filters_by_registered_domain_and_status() {
+        let root_dir = temp_root();
+        let cache = FileCache::new(CacheConfig {
+            root_dir: root_dir.clone(),
+            ..Default::default()
+        });
+        cache.init().unwrap();
+
+        for (url, status, fetched_at) in [
+            ("https://www.example.com/a", 200, "10"),
+            ("https://blog.example.com/b", 200, "20"),
+            ("https://example.com/gone", 404, "30"),
+            ("https://other.com/c", 200, "40"),
+        ] {
+            let mut page = make_cached_page(url, url, status, HashMap::new(), "");
+            page.fetch.fetched_at = fetched_at.to_string();
+            cache.store(page).unwrap();
+        }
+
+        let query = CacheQuery {
+            domain: Some("example.com".to_string()),
+            status: Some(200),
+            limit: None,
+        };
+        let urls: Vec<String> = cache
+            .list(&query)
+            .unwrap()
+            .into_iter()
+            .map(|f| f.final_url)
+            .collect();
+        assert_eq!(
+            urls,
+            vec!["https://blog.example.com/b", "https://www.example.com/a"]
+        );
+
+        let newest = cache
+            .list(&CacheQuery {
+                limit: Some(1),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(newest.len(), 1);
+        assert_eq!(newest[0].final_url, "https://other.com/c");
+
+        fs::remove_dir_all(root_dir).unwrap();
+    }
+
+    #[test]
+    fn store_swaps_entry_in_and_leaves_no_staging_dirs() {
+        let root_dir = temp_root();
+        let cache = FileCache::new(CacheConfig {
+            root_dir: root_dir.clone(),
+            ..Default::default()
+        });
+        cache.init().unwrap();
+
+        let url = "https://example.com/news";
+        cache
+            .store(make_cached_page(url, url, 200, HashMap::new(), "first"))
+            .unwrap();
+        let key = cache
+            .store(make_cached_page(url, url, 200, HashMap::new(), "second"))
+            .unwrap();
+
+        assert_eq!(cache.load(&key).unwrap().unwrap().html, "second");
+        assert_eq!(fs::read_dir(root_dir.join("tmp")).unwrap().count(), 0);
+
+        fs::remove_dir_all(root_dir).unwrap();
+    }
+
+    #[test]
+    fn concurrent_writers_never_expose_torn_entries() {
+        let root_dir = temp_root();
+        let config = CacheConfig {
+            root_dir: root_dir.clone(),
+            ..Default::default()
+        };
+        FileCache::new(config.clone()).init().unwrap();
+
+        let url = "https://example.com/news";
+        let handles: Vec<_> = (0..4)
+            .map(|writer| {
+                let cache = FileCache::new(config.clone());
+                std::thread::spawn(move || {
+                    for round in 0..10 {
+                        let html = format!("writer-{writer}-round-{round}");
+                        let mut headers = HashMap::new();
+                        headers.insert("x-body".to_string(), html.clone());
+                        cache
+                            .store(make_cached_page(url, url, 200, headers, &html))
+                            .unwrap();
+                        let key = cache.key_for_final_url(url).unwrap();
+                        let page = cache.load(&key).unwrap().unwrap();
+                        assert_eq!(page.headers["x-body"], page.html);
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        fs::remove_dir_all(root_dir).unwrap();
+    }
+
+    #[test]
+    fn reads_take_no_lock() {
+        let root_dir = temp_root();
+        let cache = FileCache::new(CacheConfig {
+            root_dir: root_dir.clone(),
+            ..Default::default()
+        });
+        cache.init().unwrap();
+
+        let url = "https://example.com/news";
+        let key = cache
+            .store(make_cached_page(url, url, 200, HashMap::new(), "v1"))
+            .unwrap();
+        let locks_dir = root_dir.join("locks");
+        fs::remove_dir_all(&locks_dir).unwrap();
+        let _held = cache.lock_entry(&key).unwrap();
+
+        assert_eq!(cache.load(&key).unwrap().unwrap().html, "v1");
+        assert_eq!(cache.snapshots(&key).unwrap().len(), 1);
+        assert_eq!(cache.stats().unwrap().entries, 1);
+
+        fs::remove_dir_all(root_dir).unwrap();
+    }
+
+    #[test]
+    fn crash_between_renames_serves_and_restores_retired_entry() {
+        let root_dir = temp_root();
+        let cache = FileCache::new(CacheConfig {
+            root_dir: root_dir.clone(),
+            history_limit: 0,
+            ..Default::default()
+        });
+        cache.init().unwrap();
+
+        let url = "https://example.com/news";
+        let key = cache
+            .store(make_cached_page(url, url, 200, HashMap::new(), "v1"))
+            .unwrap();
+        let entry_dir = root_dir.join("pages").join(&key.hash);
+        let retired = root_dir.join("tmp").join(format!("{}.old.1.1", key.hash));
+        fs::rename(&entry_dir, &retired).unwrap();
+
+        assert_eq!(cache.load(&key).unwrap().unwrap().html, "v1");
+
+        cache.init().unwrap();
+        assert!(entry_dir.exists());
+        assert!(!retired.exists());
+        assert_eq!(cache.list(&CacheQuery::default()).unwrap().len(), 1);
+
+        fs::remove_dir_all(root_dir).unwrap();
+    }
+
+    #[test]
+    fn crash_after_moving_entry_to_history_serves_newest_snapshot() {
+        let root_dir = temp_root();
+        let cache = FileCache::new(CacheConfig {
+            root_dir: root_dir.clone(),
+            ..Default::default()
+        });
+        cache.init().unwrap();
+
+        let url = "https://example.com/news";
+        for (fetched_at, html) in [("10", "v1"), ("20", "v2")] {
+            let mut page = make_cached_page(url, url, 200, HashMap::new(), html);
+            page.fetch.fetched_at = fetched_at.to_string();
+            cache.store(page).unwrap();
+        }
+        let key = cache.key_for_final_url(url).unwrap();
+        fs::rename(
+            root_dir.join("pages").join(&key.hash),
+            root_dir
+                .join("history")
+                .join(&key.hash)
+                .join(format!("{:020}.1.1", 20)),
+        )
+        .unwrap();
+
+        assert_eq!(cache.load(&key).unwrap().unwrap().html, "v2");
+        let html: Vec<String> = cache
+            .snapshots(&key)
+            .unwrap()
+            .into_iter()
+            .map(|page| page.html)
+            .collect();
+        assert_eq!(html, vec!["v2", "v1"]);
+
+        fs::remove_dir_all(root_dir).unwrap();
+    }
+
+    #[test]
+    fn overwriting_keeps_limited_snapshot_history() {
+        let root_dir = temp_root();
+        let cache = FileCache::new(CacheConfig {
+            root_dir: root_dir.clone(),
+            history_limit: 2,
+            ..Default::default()
+        });
+        cache.init().unwrap();
+
+        let url = "https://example.com/news";
+        for (fetched_at, html) in
+            [("10", "v1"), ("20", "v2"), ("30", "v3"), ("40", "v4")]
+        {
+            let mut page = make_cached_page(url, url, 200, HashMap::new(), html);
+            page.fetch.fetched_at = fetched_at.to_string();
+            cache.store(page).unwrap();
+        }
+
+        let key = cache.key_for_final_url(url).unwrap();
+        let html: Vec<String> = cache
+            .snapshots(&key)
+            .unwrap()
+            .into_iter()
+            .map(|page| page.html)
+            .collect();
+        assert_eq!(html, vec!["v4", "v3", "v2"]);
+
+        cache.migrate(Compression::Gzip).unwrap();
+        assert_eq!(cache.snapshots(&key).unwrap().len(), 3);
+        assert_eq!(cache.stats().unwrap().entries, 1);
+
+        cache.delete(&key).unwrap();
+        assert!(cache.snapshots(&key).unwrap().is_empty());
+        assert!(!root_dir.join("history").join(&key.hash).exists());
+
+        fs::remove_dir_all(root_dir).unwrap();
+    }
+
+    #[test]
+    fn migrate_and_stats_cover_history_snapshots() {
+        let root_dir = temp_root();
+        let cache = FileCache::new(CacheConfig {
+            root_dir: root_dir.clone(),
+            history_limit: 2,
+            ..Default::default()
+        });
+        cache.init().unwrap();
+
+        let url = "https://example.com/news";
+        for (fetched_at, html) in [("10", "v1"), ("20", "v2"), ("30", "v3")] {
+            let mut page = make_cached_page(url, url, 200, HashMap::new(), html);
+            page.fetch.fetched_at = fetched_at.to_string();
+            cache.store(page).unwrap();
+        }
+        let stats = cache.stats().unwrap();
+        assert_eq!((stats.entries, stats.snapshots), (1, 2));
+        assert_eq!(stats.body_bytes, 6);
+        assert_eq!(stats.by_compression.get("none"), Some(&3));
+
+        assert_eq!(cache.migrate(Compression::Gzip).unwrap(), 3);
+        assert_eq!(cache.migrate(Compression::Gzip).unwrap(), 0);
+
+        let key = cache.key_for_final_url(url).unwrap();
+        let html: Vec<String> = cache
+            .snapshots(&key)
+            .unwrap()
+            .into_iter()
+            .map(|page| page.html)
+            .collect();
+        assert_eq!(html, vec!["v3", "v2", "v1"]);
+        for snapshot in cache.history_snapshots(&key).unwrap() {
+            assert!(snapshot.join("page.html.gz").exists());
+            assert!(!snapshot.join("page.html").exists());
+        }
+        let stats = cache.stats().unwrap();
+        assert_eq!((stats.entries, stats.snapshots), (1, 2));
+        assert_eq!(stats.body_bytes, 6);
+        assert_eq!(stats.by_compression.get("gzip"), Some(&3));
+        assert_eq!(stats.by_compression.get("none"), None);
+
+        fs::remove_dir_all(root_dir).unwrap();
+    }
+
+    #[test]
+    fn zero_history_limit_discards_previous_entry() {
+        let root_dir = temp_root();
+        let cache = FileCache::new(CacheConfig {
+            root_dir: root_dir.clone(),
+            history_limit: 0,
+            ..Default::default()
+        });
+        cache.init().unwrap();
+
+        let url = "https://example.com/news";
+        cache
+            .store(make_cached_page(url, url, 200, HashMap::new(), "v1"))
+            .unwrap();
+        let key = cache
+            .store(make_cached_page(url, url, 200, HashMap::new(), "v2"))
+            .unwrap();
+
+        assert_eq!(cache.snapshots(&key).unwrap().len(), 1);
+        assert!(!root_dir.join("history").exists());
+
+        fs::remove_dir_all(root_dir).unwrap();
+    }
+
+    #[test]
+    fn init_sweeps_stale_staging_dirs_and_files() {
+        let root_dir = temp_root();
+        let cache = FileCache::new(CacheConfig {
+            root_dir: root_dir.clone(),
+            ..Default::default()
+        });
+        cache.init().unwrap();
+
+        let stale = root_dir.join("tmp").join("abc.1.1");
+        fs::create_dir_all(&stale).unwrap();
+        fs::File::open(&stale)
+            .unwrap()
+            .set_modified(SystemTime::now() - STALE_TMP_AGE * 2)
+            .unwrap();
+        let fresh = root_dir.join("tmp").join("def.1.2");
+        fs::create_dir_all(&fresh).unwrap();
+        // A writer that crashed while replacing VERSION leaves a plain file.
+        let stale_file = root_dir.join("tmp").join("VERSION.1.3");
+        fs::File::create(&stale_file)
+            .unwrap()
+            .set_modified(SystemTime::now() - STALE_TMP_AGE * 2)
+            .unwrap();
+        let fresh_file = root_dir.join("tmp").join("VERSION.1.4");
+        fs::write(&fresh_file, "1").unwrap();
+
+        cache.init().unwrap();
+        assert!(!stale.exists());
+        assert!(fresh.exists());
+        assert!(!stale_file.exists());
+        assert!(fresh_file.exists());
+
+        fs::remove_dir_all(root_dir).unwrap();
+    }
+
     #[test]
     fn init_fails_on_version_mismatch() {
         let root_dir = temp_root();
@@ -260,6 +1288,7 @@ mod tests {
             root_dir: root_dir.clone(),
             enabled: true,
             refresh: false,
+            ..Default::default()
         });
 
         let result = cache.init();
@@ -281,6 +1310,7 @@ mod tests {
             root_dir: root_dir.clone(),
             enabled: false,
             refresh: false,
+            ..Default::default()
         });
 
         cache.init().unwrap();
@@ -288,5 +1318,81 @@ mod tests {
 
         let key = cache.key_for_final_url("https://example.com/news").unwrap();
         assert!(cache.load(&key).unwrap().is_none());
+
+        let page = make_cached_page(
+            "https://example.com/news",
+            "https://example.com/news",
+            200,
+            HashMap::new(),
+            "<html></html>",
+        );
+        assert_eq!(cache.store(page).unwrap(), key);
+        assert!(!root_dir.exists());
+    }
+
+    fn failure(url: &str, failed_at: u64) -> CachedFailure {
+        CachedFailure {
+            url: url.to_string(),
+            kind: crate::cache::FailureKind::Http,
+            status: Some(404),
+            reason: None,
+            attempts: 1,
+            failed_at: failed_at.to_string(),
+        }
+    }
+
+    #[test]
+    fn failures_expire_and_are_cleared_by_a_successful_store() {
+        let root_dir = temp_root();
+        let cache = FileCache::new(CacheConfig {
+            root_dir: root_dir.clone(),
+            failure_ttl_secs: 60,
+            ..Default::default()
+        });
+        cache.init().unwrap();
+        let url = "https://example.com/gone";
+        let key = cache.key_for_final_url(url).unwrap();
+
+        cache
+            .store_failure(&key, &failure(url, now_secs()))
+            .unwrap();
+        let loaded = cache.load_failure(&key).unwrap().unwrap();
+        assert_eq!(loaded.status, Some(404));
+
+        cache
+            .store_failure(&key, &failure(url, now_secs() - 120))
+            .unwrap();
+        assert!(cache.load_failure(&key).unwrap().is_none());
+
+        cache
+            .store_failure(&key, &failure(url, now_secs()))
+            .unwrap();
+        cache
+            .store(make_cached_page(url, url, 200, HashMap::new(), "<html>"))
+            .unwrap();
+        assert!(cache.load_failure(&key).unwrap().is_none());
+
+        fs::remove_dir_all(root_dir).unwrap();
+    }
+
+    #[test]
+    fn zero_failure_ttl_disables_negative_caching() {
+        let root_dir = temp_root();
+        let cache = FileCache::new(CacheConfig {
+            root_dir: root_dir.clone(),
+            failure_ttl_secs: 0,
+            ..Default::default()
+        });
+        cache.init().unwrap();
+        let url = "https://example.com/gone";
+        let key = cache.key_for_final_url(url).unwrap();
+
+        cache
+            .store_failure(&key, &failure(url, now_secs()))
+            .unwrap();
+        assert!(!root_dir.join("failures").exists());
+        assert!(cache.load_failure(&key).unwrap().is_none());
+
+        fs::remove_dir_all(root_dir).unwrap();
     }
 }
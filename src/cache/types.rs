@@ -1,15 +1,58 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 
 use serde::{Deserialize, Serialize};
 
-const CACHE_DIR: &str = ".pginf";
+use crate::cache::compression::Compression;
+use crate::normalize::NormalizePolicy;
+
+pub(crate) const CACHE_DIR: &str = ".pginf";
+pub const DEFAULT_HISTORY_LIMIT: usize = 5;
+pub const DEFAULT_FAILURE_TTL_SECS: u64 = 300;
+
+/// Storage backend behind the `Cache` trait.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheBackend {
+    /// One directory per page under `pages/`.
+    #[default]
+    File,
+    /// Single `cache.sqlite` file with indexed columns.
+    Sqlite,
+}
+
+impl CacheBackend {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "file" => Some(Self::File),
+            "sqlite" => Some(Self::Sqlite),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::File => "file",
+            Self::Sqlite => "sqlite",
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct CacheConfig {
     pub root_dir: PathBuf,
     pub enabled: bool,
     pub refresh: bool,
+    pub compression: Compression,
+    pub backend: CacheBackend,
+    /// How many previous snapshots to keep per entry when it is overwritten.
+    pub history_limit: usize,
+    /// Serve pages from the cache only; a miss is an error instead of a fetch.
+    pub offline: bool,
+    /// Extra URL normalization applied when deriving cache keys.
+    pub normalization: NormalizePolicy,
+    /// How long a failed fetch is remembered; 0 disables negative caching.
+    pub failure_ttl_secs: u64,
 }
 
 impl Default for CacheConfig {
@@ -18,17 +61,47 @@ impl Default for CacheConfig {
             root_dir: PathBuf::from(CACHE_DIR),
             enabled: true,
             refresh: false,
+            compression: Compression::None,
+            backend: CacheBackend::File,
+            history_limit: DEFAULT_HISTORY_LIMIT,
+            offline: false,
+            normalization: NormalizePolicy::default(),
+            failure_ttl_secs: DEFAULT_FAILURE_TTL_SECS,
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CachedFetch {
     pub input_url: String,
     pub final_url: String,
     pub normalized_final_url: String,
     pub status: u16,
     pub fetched_at: String,
+    #[serde(default)]
+    pub duration_ms: u64,
+    #[serde(default)]
+    pub emulation_used: Option<String>,
+    #[serde(default)]
+    pub proxy_used: Option<String>,
+    #[serde(default)]
+    pub attempts: usize,
+    #[serde(default)]
+    pub body_size: usize,
+    #[serde(default)]
+    pub content_type: Option<String>,
+}
+
+impl CachedFetch {
+    /// Registered domain of the final URL, falling back to its host.
+    pub fn domain(&self) -> Option<String> {
+        let url = url::Url::parse(&self.final_url).ok()?;
+        Some(registered_domain(url.host_str()?))
+    }
+
+    pub fn fetched_at_secs(&self) -> u64 {
+        self.fetched_at.parse().unwrap_or(0)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -37,3 +110,91 @@ pub struct CachedPage {
     pub headers: HashMap<String, String>,
     pub html: String,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureKind {
+    /// The server answered with an error status.
+    Http,
+    /// DNS, connect, TLS, or body read error.
+    Request,
+    /// Every emulation was tried without success.
+    AllAttemptsFailed,
+}
+
+/// A failed fetch, remembered for `CacheConfig::failure_ttl_secs` so broken
+/// URLs are not refetched in a loop.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedFailure {
+    pub url: String,
+    pub kind: FailureKind,
+    #[serde(default)]
+    pub status: Option<u16>,
+    #[serde(default)]
+    pub reason: Option<String>,
+    pub attempts: usize,
+    pub failed_at: String,
+}
+
+impl CachedFailure {
+    pub fn failed_at_secs(&self) -> u64 {
+        self.failed_at.parse().unwrap_or(0)
+    }
+
+    /// Whether the failure is still within `ttl_secs` at `now_secs`.
+    pub fn is_fresh(&self, ttl_secs: u64, now_secs: u64) -> bool {
+        now_secs.saturating_sub(self.failed_at_secs()) < ttl_secs
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CacheStats {
+    /// Current entries.
+    pub entries: usize,
+    /// History snapshots kept besides the current entries. Sizes and
+    /// `by_compression` cover both.
+    pub snapshots: usize,
+    pub body_bytes: u64,
+    pub stored_bytes: u64,
+    pub by_compression: BTreeMap<String, usize>,
+}
+
+impl CacheStats {
+    pub fn saved_bytes(&self) -> u64 {
+        self.body_bytes.saturating_sub(self.stored_bytes)
+    }
+}
+
+/// Filter for listing cache entries. Empty fields match everything.
+#[derive(Debug, Clone, Default)]
+pub struct CacheQuery {
+    pub domain: Option<String>,
+    pub status: Option<u16>,
+    pub limit: Option<usize>,
+}
+
+impl CacheQuery {
+    pub fn matches(&self, fetch: &CachedFetch) -> bool {
+        if let Some(status) = self.status
+            && fetch.status != status
+        {
+            return false;
+        }
+        if let Some(domain) = &self.domain
+            && fetch.domain() != Some(registered_domain(domain))
+        {
+            return false;
+        }
+        true
+    }
+}
+
+/// Reduces a host to its registered domain (`www.example.co.uk` ->
+/// `example.co.uk`), keeping IP addresses and unknown suffixes as-is.
+pub(crate) fn registered_domain(host: &str) -> String {
+    let host = host.to_ascii_lowercase();
+    if host.parse::<std::net::IpAddr>().is_ok() {
+        return host;
+    }
+    psl::domain_str(&host).unwrap_or(&host).to_string()
+}
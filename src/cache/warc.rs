@@ -0,0 +1,761 @@
+//! Minimal WARC 1.1 support for moving pages between the cache and other
+//! crawlers. Each page becomes a `request`, `response`, and `metadata`
+//! record; compressed files use one gzip member per record.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use flate2::read::MultiGzDecoder;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use url::Url;
+
+use crate::cache::CacheError;
+use crate::cache::compression::Compression;
+use crate::cache::key::normalize_url;
+use crate::cache::store::{Cache, now_secs};
+use crate::cache::types::{CacheQuery, CachedFetch, CachedPage};
+
+const WARC_VERSION: &str = "WARC/1.1";
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Headers describing the stored body rather than the page; the cache keeps
+/// decoded bodies, so these would be wrong in the exported response.
+const TRANSFER_HEADERS: &[&str] =
+    &["content-encoding", "content-length", "transfer-encoding"];
+
+/// Outcome of importing a WARC file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ImportSummary {
+    pub imported: usize,
+    /// Responses that are not HTML (images, scripts, ...).
+    pub skipped_non_html: usize,
+    /// Responses whose payload could not be decoded.
+    pub skipped_unreadable: usize,
+    /// Records that could not be parsed; the import resumes at the next
+    /// WARC version line.
+    pub skipped_malformed: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+struct WarcRecord {
+    headers: Vec<(String, String)>,
+    block: Vec<u8>,
+}
+
+impl WarcRecord {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn record_type(&self) -> &str {
+        self.header("WARC-Type").unwrap_or_default()
+    }
+
+    fn write_to(&self, out: &mut impl Write, gzip: bool) -> io::Result<()> {
+        let mut raw = Vec::with_capacity(self.block.len() + 512);
+        raw.extend_from_slice(WARC_VERSION.as_bytes());
+        raw.extend_from_slice(b"\r\n");
+        for (name, value) in &self.headers {
+            raw.extend_from_slice(format!("{name}: {value}\r\n").as_bytes());
+        }
+        raw.extend_from_slice(
+            format!("Content-Length: {}\r\n\r\n", self.block.len()).as_bytes(),
+        );
+        raw.extend_from_slice(&self.block);
+        raw.extend_from_slice(b"\r\n\r\n");
+
+        if gzip {
+            out.write_all(&Compression::Gzip.encode(&raw)?)
+        } else {
+            out.write_all(&raw)
+        }
+    }
+}
+
+/// Writes every cached page to `path`, gzip-compressed when the name ends in
+/// `.gz`. Returns the number of pages exported.
+pub fn export_to_path(cache: &dyn Cache, path: &Path) -> Result<usize, CacheError> {
+    let gzip = path.extension().is_some_and(|ext| ext == "gz");
+    let mut out = BufWriter::new(File::create(path)?);
+    let exported = export(cache, &mut out, gzip)?;
+    out.flush()?;
+    Ok(exported)
+}
+
+pub fn export(
+    cache: &dyn Cache,
+    out: &mut impl Write,
+    gzip: bool,
+) -> Result<usize, CacheError> {
+    let mut fetches = cache.list(&CacheQuery::default())?;
+    fetches.reverse();
+
+    warcinfo_record().write_to(out, gzip)?;
+    let mut exported = 0;
+    for fetch in fetches {
+        let key = cache.key_for_final_url(&fetch.final_url)?;
+        let Some(page) = cache.load(&key)? else {
+            continue;
+        };
+        for record in page_records(&page)? {
+            record.write_to(out, gzip)?;
+        }
+        exported += 1;
+    }
+    Ok(exported)
+}
+
+/// Reads a WARC file (plain or gzip, detected from its first bytes) and
+/// stores its HTML responses in `cache`.
+pub fn import_from_path(
+    cache: &dyn Cache,
+    path: &Path,
+) -> Result<ImportSummary, CacheError> {
+    let mut file = BufReader::new(File::open(path)?);
+    let gzip = file.fill_buf()?.starts_with(&GZIP_MAGIC);
+    if gzip {
+        import(cache, BufReader::new(MultiGzDecoder::new(file)))
+    } else {
+        import(cache, file)
+    }
+}
+
+pub fn import(
+    cache: &dyn Cache,
+    mut input: impl BufRead,
+) -> Result<ImportSummary, CacheError> {
+    let mut summary = ImportSummary::default();
+    // A response is held back until the next record, which may be our own
+    // metadata record carrying the original fetch details.
+    let mut pending: Option<(String, CachedPage)> = None;
+
+    let mut next = read_record(&mut input);
+    loop {
+        let record = match next {
+            Ok(Some(record)) => record,
+            Ok(None) => break,
+            Err(CacheError::Warc(_)) => {
+                summary.skipped_malformed += 1;
+                next = resync(&mut input);
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        match record.record_type() {
+            "response" => {
+                if let Some((_, page)) = pending.take() {
+                    cache.store(page)?;
+                    summary.imported += 1;
+                }
+                match response_page(&record) {
+                    Ok(Some(page)) => {
+                        let id = record
+                            .header("WARC-Record-ID")
+                            .unwrap_or_default()
+                            .to_string();
+                        pending = Some((id, page));
+                    }
+                    Ok(None) => summary.skipped_non_html += 1,
+                    Err(_) => summary.skipped_unreadable += 1,
+                }
+            }
+            "metadata" => {
+                if let Some((id, page)) = &mut pending
+                    && record.header("WARC-Concurrent-To") == Some(id.as_str())
+                    && let Ok(fetch) =
+                        serde_json::from_slice::<CachedFetch>(&record.block)
+                {
+                    page.fetch = CachedFetch {
+                        body_size: page.html.len(),
+                        ..fetch
+                    };
+                }
+            }
+            _ => {}
+        }
+        next = read_record(&mut input);
+    }
+    if let Some((_, page)) = pending {
+        cache.store(page)?;
+        summary.imported += 1;
+    }
+    Ok(summary)
+}
+
+fn warcinfo_record() -> WarcRecord {
+    let block = format!(
+        "software: pginf/{}\r\nformat: WARC File Format 1.1\r\n",
+        env!("CARGO_PKG_VERSION")
+    );
+    WarcRecord {
+        headers: vec![
+            ("WARC-Type".to_string(), "warcinfo".to_string()),
+            ("WARC-Record-ID".to_string(), record_id("warcinfo", "", 0)),
+            ("WARC-Date".to_string(), format_warc_date(now_secs())),
+            (
+                "Content-Type".to_string(),
+                "application/warc-fields".to_string(),
+            ),
+        ],
+        block: block.into_bytes(),
+    }
+}
+
+fn page_records(page: &CachedPage) -> Result<[WarcRecord; 3], CacheError> {
+    let fetch = &page.fetch;
+    let url = Url::parse(&fetch.final_url)
+        .map_err(|e| CacheError::InvalidUrl(e.to_string()))?;
+    let secs = fetch.fetched_at_secs();
+    let date = format_warc_date(secs);
+    let request_id = record_id("request", &fetch.final_url, secs);
+    let response_id = record_id("response", &fetch.final_url, secs);
+
+    let record =
+        |kind: &str, id: &str, content_type: &str, block: Vec<u8>| WarcRecord {
+            headers: vec![
+                ("WARC-Type".to_string(), kind.to_string()),
+                ("WARC-Record-ID".to_string(), id.to_string()),
+                ("WARC-Date".to_string(), date.clone()),
+                ("WARC-Target-URI".to_string(), fetch.final_url.clone()),
+                ("Content-Type".to_string(), content_type.to_string()),
+            ],
+            block,
+        };
+
+    let mut request = record(
+        "request",
+        &request_id,
+        "application/http;msgtype=request",
+        request_block(&url).into_bytes(),
+    );
+    request
+        .headers
+        .push(("WARC-Concurrent-To".to_string(), response_id.clone()));
+
+    let response = record(
+        "response",
+        &response_id,
+        "application/http;msgtype=response",
+        response_block(page),
+    );
+
+    let mut metadata = record(
+        "metadata",
+        &record_id("metadata", &fetch.final_url, secs),
+        "application/json",
+        serde_json::to_vec_pretty(fetch)?,
+    );
+    metadata
+        .headers
+        .push(("WARC-Concurrent-To".to_string(), response_id));
+
+    Ok([request, response, metadata])
+}
+
+fn request_block(url: &Url) -> String {
+    let mut target = url.path().to_string();
+    if let Some(query) = url.query() {
+        target.push('?');
+        target.push_str(query);
+    }
+    let host = match url.port() {
+        Some(port) => format!("{}:{port}", url.host_str().unwrap_or_default()),
+        None => url.host_str().unwrap_or_default().to_string(),
+    };
+    format!("GET {target} HTTP/1.1\r\nHost: {host}\r\n\r\n")
+}
+
+fn response_block(page: &CachedPage) -> Vec<u8> {
+    let reason = wreq::StatusCode::from_u16(page.fetch.status)
+        .ok()
+        .and_then(|status| status.canonical_reason())
+        .unwrap_or_default();
+    let mut head = format!("HTTP/1.1 {} {reason}\r\n", page.fetch.status);
+
+    let mut headers: Vec<_> = page
+        .headers
+        .iter()
+        .filter(|(name, _)| {
+            !TRANSFER_HEADERS.contains(&name.to_ascii_lowercase().as_str())
+        })
+        .collect();
+    headers.sort();
+    for (name, value) in headers {
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+    head.push_str(&format!("content-length: {}\r\n\r\n", page.html.len()));
+
+    let mut block = head.into_bytes();
+    block.extend_from_slice(page.html.as_bytes());
+    block
+}
+
+/// Reads the next record, or `None` at end of input.
+fn read_record(input: &mut impl BufRead) -> Result<Option<WarcRecord>, CacheError> {
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        if !line.trim().is_empty() {
+            break;
+        }
+    }
+    if !line.starts_with("WARC/") {
+        return Err(CacheError::Warc(format!(
+            "expected a WARC version line, found {:?}",
+            line.trim_end()
+        )));
+    }
+    read_record_fields(input).map(Some)
+}
+
+/// Skips past a malformed record to the next version line and reads the
+/// record it starts.
+fn resync(input: &mut impl BufRead) -> Result<Option<WarcRecord>, CacheError> {
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        if line.starts_with("WARC/") {
+            return read_record_fields(input).map(Some);
+        }
+    }
+}
+
+/// Reads the header fields and block following a version line.
+fn read_record_fields(input: &mut impl BufRead) -> Result<WarcRecord, CacheError> {
+    let mut line = String::new();
+    let mut record = WarcRecord::default();
+    loop {
+        line.clear();
+        if input.read_line(&mut line)? == 0 {
+            return Err(CacheError::Warc("truncated record header".to_string()));
+        }
+        let trimmed = line.trim_end_matches(['\r', '\n']);
+        if trimmed.is_empty() {
+            break;
+        }
+        if trimmed.starts_with([' ', '\t'])
+            && let Some((_, value)) = record.headers.last_mut()
+        {
+            value.push(' ');
+            value.push_str(trimmed.trim());
+        } else if let Some((name, value)) = trimmed.split_once(':') {
+            record
+                .headers
+                .push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+
+    let length: u64 = record
+        .header("Content-Length")
+        .and_then(|value| value.parse().ok())
+        .ok_or_else(|| CacheError::Warc("missing Content-Length".to_string()))?;
+    // The length comes from the file, so the block grows with the bytes
+    // actually read instead of being allocated up front.
+    let read = input.by_ref().take(length).read_to_end(&mut record.block)?;
+    if (read as u64) < length {
+        return Err(CacheError::Warc(format!(
+            "truncated record block: expected {length} bytes, found {read}"
+        )));
+    }
+    Ok(record)
+}
+
+/// Turns a response record into a cached page. `Ok(None)` means the response
+/// is not HTML.
+fn response_page(record: &WarcRecord) -> Result<Option<CachedPage>, CacheError> {
+    let target = record
+        .header("WARC-Target-URI")
+        .map(|uri| uri.trim_matches(['<', '>']))
+        .ok_or_else(|| CacheError::Warc("response without target URI".into()))?;
+    let normalized_final_url = normalize_url(target)?;
+
+    let split = find_subslice(&record.block, b"\r\n\r\n")
+        .map(|at| (at, at + 4))
+        .or_else(|| find_subslice(&record.block, b"\n\n").map(|at| (at, at + 2)))
+        .ok_or_else(|| CacheError::Warc("response without HTTP headers".into()))?;
+    let head = String::from_utf8_lossy(&record.block[..split.0]);
+    let mut lines = head.lines();
+    let status = lines
+        .next()
+        .and_then(|status_line| status_line.split_whitespace().nth(1))
+        .and_then(|code| code.parse::<u16>().ok())
+        .ok_or_else(|| CacheError::Warc("invalid HTTP status line".into()))?;
+
+    let mut headers = HashMap::new();
+    for header in lines {
+        if let Some((name, value)) = header.split_once(':') {
+            headers
+                .insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+        }
+    }
+
+    let content_type = headers.get("content-type").cloned();
+    if content_type
+        .as_deref()
+        .is_some_and(|value| !value.to_ascii_lowercase().contains("html"))
+    {
+        return Ok(None);
+    }
+
+    let mut body = record.block[split.1..].to_vec();
+    if headers
+        .get("transfer-encoding")
+        .is_some_and(|value| value.eq_ignore_ascii_case("chunked"))
+    {
+        body = dechunk(&body)?;
+    }
+    body = match headers
+        .get("content-encoding")
+        .map(|v| v.to_ascii_lowercase())
+    {
+        None => body,
+        Some(encoding) => match encoding.as_str() {
+            "identity" => body,
+            "gzip" | "x-gzip" => Compression::Gzip.decode(&body)?,
+            "zstd" => Compression::Zstd.decode(&body)?,
+            other => {
+                return Err(CacheError::Warc(format!(
+                    "unsupported content encoding {other}"
+                )));
+            }
+        },
+    };
+    for name in TRANSFER_HEADERS {
+        headers.remove(*name);
+    }
+
+    let html = String::from_utf8_lossy(&body).into_owned();
+    let fetched_at = record
+        .header("WARC-Date")
+        .and_then(parse_warc_date)
+        .unwrap_or_else(now_secs);
+    Ok(Some(CachedPage {
+        fetch: CachedFetch {
+            input_url: target.to_string(),
+            final_url: target.to_string(),
+            normalized_final_url,
+            status,
+            fetched_at: fetched_at.to_string(),
+            body_size: html.len(),
+            content_type,
+            ..Default::default()
+        },
+        headers,
+        html,
+    }))
+}
+
+fn dechunk(mut data: &[u8]) -> Result<Vec<u8>, CacheError> {
+    let invalid = || CacheError::Warc("invalid chunked encoding".to_string());
+    let mut out = Vec::new();
+    loop {
+        let line_end = find_subslice(data, b"\r\n").ok_or_else(invalid)?;
+        let size_line = String::from_utf8_lossy(&data[..line_end]);
+        let size_hex = size_line.split(';').next().unwrap_or_default().trim();
+        let size = usize::from_str_radix(size_hex, 16).map_err(|_| invalid())?;
+        data = &data[line_end + 2..];
+        if size == 0 {
+            return Ok(out);
+        }
+        let chunk = data.get(..size).ok_or_else(invalid)?;
+        out.extend_from_slice(chunk);
+        data = data.get(size + 2..).unwrap_or_default();
+    }
+}
+
+fn find_subslice(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// Deterministic `urn:uuid:` identifier so re-exports produce stable IDs.
+fn record_id(kind: &str, url: &str, secs: u64) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(kind.as_bytes());
+    hasher.update(url.as_bytes());
+    hasher.update(secs.to_le_bytes());
+    if url.is_empty() {
+        hasher.update(now_nanos().to_le_bytes());
+    }
+    let hex: String = hasher.finalize()[..16]
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect();
+    format!(
+        "<urn:uuid:{}-{}-{}-{}-{}>",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+fn now_nanos() -> u128 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0)
+}
+
+/// Formats Unix seconds as the UTC `YYYY-MM-DDThh:mm:ssZ` form WARC uses.
+fn format_warc_date(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+/// Parses a WARC date, ignoring fractional seconds.
+fn parse_warc_date(value: &str) -> Option<u64> {
+    let value = value.get(..19)?;
+    let field =
+        |range: std::ops::Range<usize>| value.get(range)?.parse::<i64>().ok();
+    let (year, month, day) = (field(0..4)?, field(5..7)?, field(8..10)?);
+    let (hour, minute, second) = (field(11..13)?, field(14..16)?, field(17..19)?);
+    let days = days_from_civil(year, month, day);
+    u64::try_from(days * 86_400 + hour * 3600 + minute * 60 + second).ok()
+}
+
+// Howard Hinnant's civil calendar conversions.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::*;
+    use crate::cache::store::FileCache;
+    use crate::cache::types::CacheConfig;
+
+    fn temp_cache(name: &str) -> (FileCache, PathBuf) {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let root =
+            std::env::temp_dir().join(format!("pageinfo-warc-{name}-{nanos}"));
+        let cache = FileCache::new(CacheConfig {
+            root_dir: root.clone(),
+            ..Default::default()
+        });
+        cache.init().unwrap();
+        (cache, root)
+    }
+
+    fn page(url: &str, html: &str) -> CachedPage {
+        CachedPage {
+            fetch: CachedFetch {
+                input_url: format!("{url}?from=input"),
+                final_url: url.to_string(),
+                normalized_final_url: normalize_url(url).unwrap(),
+                status: 200,
+                fetched_at: "1700000000".to_string(),
+                duration_ms: 42,
+                emulation_used: Some("chrome".to_string()),
+                attempts: 1,
+                body_size: html.len(),
+                content_type: Some("text/html".to_string()),
+                ..Default::default()
+            },
+            headers: HashMap::from([
+                ("content-type".to_string(), "text/html".to_string()),
+                ("content-encoding".to_string(), "br".to_string()),
+            ]),
+            html: html.to_string(),
+        }
+    }
+
+    #[test]
+    fn warc_dates_round_trip() {
+        assert_eq!(format_warc_date(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_warc_date(1_700_000_000), "2023-11-14T22:13:20Z");
+        assert_eq!(parse_warc_date("2023-11-14T22:13:20Z"), Some(1_700_000_000));
+        assert_eq!(
+            parse_warc_date("2024-02-29T12:00:00.123Z"),
+            Some(1_709_208_000)
+        );
+        assert_eq!(parse_warc_date("yesterday"), None);
+    }
+
+    #[test]
+    fn export_then_import_restores_pages() {
+        let (source, source_root) = temp_cache("export");
+        source
+            .store(page("https://example.com/a", "<html><h1>A</h1></html>"))
+            .unwrap();
+        source
+            .store(page("https://example.com/b", "<html><h1>B</h1></html>"))
+            .unwrap();
+
+        let warc = source_root.join("out.warc.gz");
+        assert_eq!(export_to_path(&source, &warc).unwrap(), 2);
+
+        let (target, target_root) = temp_cache("import");
+        let summary = import_from_path(&target, &warc).unwrap();
+        assert_eq!(summary.imported, 2);
+
+        let key = target.key_for_final_url("https://example.com/a").unwrap();
+        let loaded = target.load(&key).unwrap().unwrap();
+        assert_eq!(loaded.html, "<html><h1>A</h1></html>");
+        assert_eq!(loaded.fetch.fetched_at, "1700000000");
+        assert_eq!(loaded.fetch.input_url, "https://example.com/a?from=input");
+        assert_eq!(loaded.fetch.duration_ms, 42);
+        assert!(!loaded.headers.contains_key("content-encoding"));
+
+        fs_cleanup(&[source_root, target_root]);
+    }
+
+    #[test]
+    fn imports_foreign_chunked_gzip_responses_and_skips_assets() {
+        let body = Compression::Gzip.encode(b"<p>hello</p>").unwrap();
+        let mut chunked = format!("{:x}\r\n", body.len()).into_bytes();
+        chunked.extend_from_slice(&body);
+        chunked.extend_from_slice(b"\r\n0\r\n\r\n");
+
+        let mut html_block = b"HTTP/1.1 200 OK\r\n\
+            Content-Type: text/html; charset=utf-8\r\n\
+            Content-Encoding: gzip\r\n\
+            Transfer-Encoding: chunked\r\n\r\n"
+            .to_vec();
+        html_block.extend_from_slice(&chunked);
+
+        let records = [
+            WarcRecord {
+                headers: vec![
+                    ("WARC-Type".to_string(), "response".to_string()),
+                    ("WARC-Date".to_string(), "2024-01-01T00:00:00Z".to_string()),
+                    (
+                        "WARC-Target-URI".to_string(),
+                        "<https://other.example/page>".to_string(),
+                    ),
+                ],
+                block: html_block,
+            },
+            WarcRecord {
+                headers: vec![
+                    ("WARC-Type".to_string(), "response".to_string()),
+                    (
+                        "WARC-Target-URI".to_string(),
+                        "https://other.example/logo.png".to_string(),
+                    ),
+                ],
+                block: b"HTTP/1.1 200 OK\r\nContent-Type: image/png\r\n\r\nPNG"
+                    .to_vec(),
+            },
+        ];
+        let mut warc = Vec::new();
+        for record in &records {
+            record.write_to(&mut warc, false).unwrap();
+        }
+
+        let (cache, root) = temp_cache("foreign");
+        let summary = import(&cache, warc.as_slice()).unwrap();
+        assert_eq!(
+            summary,
+            ImportSummary {
+                imported: 1,
+                skipped_non_html: 1,
+                skipped_unreadable: 0,
+                skipped_malformed: 0,
+            }
+        );
+
+        let key = cache
+            .key_for_final_url("https://other.example/page")
+            .unwrap();
+        let loaded = cache.load(&key).unwrap().unwrap();
+        assert_eq!(loaded.html, "<p>hello</p>");
+        assert_eq!(loaded.fetch.fetched_at, "1704067200");
+        assert!(!loaded.headers.contains_key("transfer-encoding"));
+
+        fs_cleanup(&[root]);
+    }
+
+    #[test]
+    fn oversized_content_length_is_a_warc_error() {
+        let warc = "WARC/1.1\r\nWARC-Type: response\r\n\
+            Content-Length: 18446744073709551615\r\n\r\nshort";
+        let err = read_record(&mut warc.as_bytes()).unwrap_err();
+        assert!(matches!(err, CacheError::Warc(_)));
+        assert!(err.to_string().contains("truncated record block"));
+
+        let (cache, root) = temp_cache("oversized");
+        let summary = import(&cache, warc.as_bytes()).unwrap();
+        assert_eq!((summary.imported, summary.skipped_malformed), (0, 1));
+
+        fs_cleanup(&[root]);
+    }
+
+    #[test]
+    fn malformed_records_are_skipped_and_counted() {
+        let (source, source_root) = temp_cache("malformed-src");
+        source
+            .store(page("https://example.com/a", "<p>a</p>"))
+            .unwrap();
+        let mut good = Vec::new();
+        export(&source, &mut good, false).unwrap();
+        let good = String::from_utf8(good).unwrap();
+
+        let warc = format!(
+            "garbage before any record\r\nmore garbage\r\n\r\n\
+             WARC/1.1\r\nWARC-Type: response\r\n\r\nno length here\r\n\r\n\
+             {good}"
+        );
+        let (cache, root) = temp_cache("malformed");
+        let summary = import(&cache, warc.as_bytes()).unwrap();
+        assert_eq!(summary.imported, 1);
+        assert_eq!(summary.skipped_malformed, 2);
+
+        let key = cache.key_for_final_url("https://example.com/a").unwrap();
+        assert_eq!(cache.load(&key).unwrap().unwrap().html, "<p>a</p>");
+
+        fs_cleanup(&[source_root, root]);
+    }
+
+    fn fs_cleanup(roots: &[PathBuf]) {
+        for root in roots {
+            let _ = std::fs::remove_dir_all(root);
+        }
+    }
+}
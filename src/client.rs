@@ -4,6 +4,8 @@ use std::time::Duration;
 use thiserror::Error;
 use url::Url;
 
+use crate::cache::{CachedFailure, FailureKind};
+
 #[derive(Debug, Error)]
 pub enum ClientError {
     #[error("fetch failed for {url}: HTTP {status}")]
@@ -18,6 +20,68 @@ pub enum ClientError {
     UnknownBrowser(String),
     #[error("all {attempts} attempts failed for {url}")]
     AllAttemptsFailed { url: String, attempts: usize },
+    #[error("not in cache: {url} (offline mode, no fetch attempted)")]
+    NotCached { url: String },
+    #[error("{error} [cached failure from {failed_at}; retry with --refresh]")]
+    CachedFailure {
+        error: Box<ClientError>,
+        failed_at: String,
+    },
+}
+
+impl ClientError {
+    /// Record for negative caching. Configuration errors and cache misses are
+    /// not worth remembering and return `None`.
+    pub fn to_cached_failure(&self, attempts: usize) -> Option<CachedFailure> {
+        let (url, kind, status, reason) = match self {
+            Self::Fetch { url, status } => {
+                (url, FailureKind::Http, Some(*status), None)
+            }
+            Self::Request { url, reason } => {
+                (url, FailureKind::Request, None, Some(reason.clone()))
+            }
+            Self::AllAttemptsFailed { url, .. } => {
+                (url, FailureKind::AllAttemptsFailed, None, None)
+            }
+            _ => return None,
+        };
+        let failed_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs().to_string())
+            .unwrap_or_else(|_| "0".to_string());
+        Some(CachedFailure {
+            url: url.clone(),
+            kind,
+            status,
+            reason,
+            attempts,
+            failed_at,
+        })
+    }
+
+    /// Rebuilds the original error from a cached failure, wrapped so callers
+    /// can tell it was not refetched.
+    pub fn from_cached_failure(failure: CachedFailure) -> Self {
+        let url = failure.url;
+        let error = match failure.kind {
+            FailureKind::Http => Self::Fetch {
+                url,
+                status: failure.status.unwrap_or(0),
+            },
+            FailureKind::Request => Self::Request {
+                url,
+                reason: failure.reason.unwrap_or_default(),
+            },
+            FailureKind::AllAttemptsFailed => Self::AllAttemptsFailed {
+                url,
+                attempts: failure.attempts,
+            },
+        };
+        Self::CachedFailure {
+            error: Box::new(error),
+            failed_at: failure.failed_at,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -49,11 +113,31 @@ impl FetchResult {
                 normalized_final_url,
                 status: self.status,
                 fetched_at,
+                duration_ms: self.duration_ms,
+                emulation_used: self.emulation_used.clone(),
+                proxy_used: self.proxy_used.clone(),
+                attempts: self.attempts,
+                body_size: self.body.len(),
+                content_type: self.headers.get("content-type").cloned(),
             },
             headers: self.headers.clone(),
             html: self.body.clone(),
         }
     }
+
+    pub fn from_cached_page(cached: crate::cache::CachedPage) -> Self {
+        Self {
+            input_url: cached.fetch.input_url,
+            final_url: cached.fetch.final_url,
+            status: cached.fetch.status,
+            headers: cached.headers,
+            body: cached.html,
+            duration_ms: cached.fetch.duration_ms,
+            emulation_used: cached.fetch.emulation_used,
+            proxy_used: cached.fetch.proxy_used,
+            attempts: cached.fetch.attempts,
+        }
+    }
 }
 
 fn mask_proxy(url: &str) -> String {
@@ -103,8 +187,19 @@ impl PageClient {
     }
 
     pub async fn fetch(&self, url: &str) -> Result<FetchResult, ClientError> {
-        let parsed =
-            Url::parse(url).map_err(|e| ClientError::InvalidUrl(e.to_string()))?;
+        self.fetch_counting_attempts(url).await.0
+    }
+
+    /// Like [`fetch`](Self::fetch), also returning the number of attempts
+    /// made, which most errors do not carry.
+    pub async fn fetch_counting_attempts(
+        &self,
+        url: &str,
+    ) -> (Result<FetchResult, ClientError>, usize) {
+        let parsed = match Url::parse(url) {
+            Ok(parsed) => parsed,
+            Err(e) => return (Err(ClientError::InvalidUrl(e.to_string())), 0),
+        };
 
         let start = std::time::Instant::now();
         let mut attempts = 0;
@@ -121,34 +216,38 @@ impl PageClient {
             }
             attempts += 1;
 
-            let client = self.build_wreq_client(browser_opt)?;
+            let client = match self.build_wreq_client(browser_opt) {
+                Ok(client) => client,
+                Err(e) => return (Err(e), attempts),
+            };
             match self.do_fetch(&client, &parsed).await {
                 Ok(mut result) => {
                     result.duration_ms = start.elapsed().as_millis() as u64;
                     result.emulation_used = browser_opt.map(|e| format!("{:?}", e));
                     result.proxy_used = self.proxy_url.as_deref().map(mask_proxy);
                     result.attempts = attempts;
-                    return Ok(result);
+                    return (Ok(result), attempts);
                 }
                 Err(e) if is_retryable(&e) => {
                     last_err = Some(e);
                     continue;
                 }
-                Err(e) => return Err(e),
+                Err(e) => return (Err(e), attempts),
             }
         }
 
-        if attempts > 0 {
-            Err(last_err.unwrap_or(ClientError::AllAttemptsFailed {
+        let err = if attempts > 0 {
+            last_err.unwrap_or(ClientError::AllAttemptsFailed {
                 url: url.to_string(),
                 attempts,
-            }))
+            })
         } else {
-            Err(ClientError::AllAttemptsFailed {
+            ClientError::AllAttemptsFailed {
                 url: url.to_string(),
                 attempts: 0,
-            })
-        }
+            }
+        };
+        (Err(err), attempts)
     }
 
     pub async fn get_raw(&self, url: &Url) -> Result<wreq::Response, ClientError> {
@@ -536,6 +635,37 @@ mod tests {
         assert_eq!(masked, "http://***@proxy.example.com:8080");
     }
 
+    #[test]
+    fn cached_page_round_trip_keeps_fetch_metadata() {
+        let mut headers = HashMap::new();
+        headers.insert("content-type".to_string(), "text/html".to_string());
+        let result = FetchResult {
+            input_url: "https://example.com".into(),
+            final_url: "https://example.com/news".into(),
+            status: 200,
+            headers,
+            body: "<html></html>".into(),
+            duration_ms: 321,
+            emulation_used: Some("Chrome136".into()),
+            proxy_used: Some("http://***@proxy:8080".into()),
+            attempts: 2,
+        };
+
+        let cached = result.to_cached_page();
+        assert_eq!(cached.fetch.body_size, 13);
+        assert_eq!(cached.fetch.content_type.as_deref(), Some("text/html"));
+
+        let restored = FetchResult::from_cached_page(cached);
+        assert_eq!(restored.duration_ms, 321);
+        assert_eq!(restored.emulation_used.as_deref(), Some("Chrome136"));
+        assert_eq!(
+            restored.proxy_used.as_deref(),
+            Some("http://***@proxy:8080")
+        );
+        assert_eq!(restored.attempts, 2);
+        assert_eq!(restored.body, "<html></html>");
+    }
+
     #[test]
     fn mask_proxy_invalid() {
         let masked = mask_proxy("not a url");
@@ -0,0 +1,123 @@
+//! Project settings read from `config.json` in the cache root
+//! (`.pginf/config.json`).
+
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::cache::CacheBackend;
+use crate::normalize::TrackingParams;
+
+pub const CONFIG_FILE: &str = "config.json";
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("failed to read {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("invalid config {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Tracking parameter patterns added to the built-in list, e.g. `ref` or
+    /// `pk_*`.
+    pub tracking_params: Vec<String>,
+    /// Serve pages from cache only; `--offline` and `PGINF_OFFLINE` win.
+    pub offline: Option<bool>,
+    /// Cache storage backend, `file` or `sqlite`; `--cache-backend` wins.
+    pub backend: Option<CacheBackend>,
+}
+
+impl Config {
+    /// Reads `config.json` from `root`; a missing file yields the defaults.
+    pub fn load(root: &Path) -> Result<Self, ConfigError> {
+        let path = root.join(CONFIG_FILE);
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Self::default());
+            }
+            Err(source) => return Err(ConfigError::Io { path, source }),
+        };
+        serde_json::from_str(&text)
+            .map_err(|source| ConfigError::Parse { path, source })
+    }
+
+    pub fn tracking_params(&self) -> TrackingParams {
+        TrackingParams::with_extra(&self.tracking_params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::*;
+
+    fn temp_root() -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let root =
+            std::env::temp_dir().join(format!("pageinfo-config-test-{nanos}"));
+        fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    #[test]
+    fn missing_file_yields_defaults() {
+        let root = temp_root();
+        assert_eq!(Config::load(&root).unwrap(), Config::default());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn reads_extra_tracking_params() {
+        let root = temp_root();
+        fs::write(
+            root.join(CONFIG_FILE),
+            r#"{"tracking_params": ["ref", "pk_*"]}"#,
+        )
+        .unwrap();
+
+        let config = Config::load(&root).unwrap();
+        assert_eq!(config.tracking_params, ["ref", "pk_*"]);
+        let params = config.tracking_params();
+        assert!(params.matches("pk_source"));
+        assert!(params.matches("utm_source"));
+
+        fs::write(root.join(CONFIG_FILE), r#"{"offline": true}"#).unwrap();
+        assert_eq!(Config::load(&root).unwrap().offline, Some(true));
+
+        fs::write(root.join(CONFIG_FILE), r#"{"backend": "sqlite"}"#).unwrap();
+        assert_eq!(
+            Config::load(&root).unwrap().backend,
+            Some(CacheBackend::Sqlite)
+        );
+
+        fs::write(root.join(CONFIG_FILE), r#"{"backend": "redis"}"#).unwrap();
+        assert!(matches!(
+            Config::load(&root),
+            Err(ConfigError::Parse { .. })
+        ));
+
+        fs::write(root.join(CONFIG_FILE), r#"{"tracking": []}"#).unwrap();
+        assert!(matches!(
+            Config::load(&root),
+            Err(ConfigError::Parse { .. })
+        ));
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -7,7 +7,10 @@ pub fn render(topic: Option<&str>) -> String {
         Some(topic) if topic == "meta" => meta_help(),
         Some(topic) if topic == "json" => json_help(),
         Some(topic) if topic == "text" => text_help(),
+        Some(topic) if topic == "contacts" => contacts_help(),
         Some(topic) if topic == "http" => http_help(),
+        Some(topic) if topic == "diff" => diff_help(),
+        Some(topic) if topic == "cache" => cache_help(),
         Some(topic) if topic == "tool" => tool_help(),
         Some(topic) => unknown_help(&topic),
     }
@@ -26,8 +29,11 @@ fn general_help() -> String {
         "- `pginf meta <URL>`: curated metadata (title, lang, description, og:type, etc.)",
         "- `pginf json <URL>`: structured data (JSON-LD, Next.js, inline JSON)",
         "- `pginf text <URL>`: extracted text content",
+        "- `pginf contacts <URL>`: emails, phone numbers, social profiles",
         "- `pginf html -u <URL>`: raw HTML, optionally filtered by CSS selector",
         "- `pginf http -u <URL>`: low-level HTTP debug (request/response details)",
+        "- `pginf diff <URL>`: compare cached snapshots of a page",
+        "- `pginf cache stats|migrate|list`: inspect and maintain the page cache",
         "- `pginf help [topic]`: built-in guide for humans and LLMs",
         "",
         "Commands expose machine-readable output via `--json` or `--format json`.",
@@ -42,9 +48,25 @@ fn general_help() -> String {
         "",
         "## Cache",
         "",
-        "- Pages are cached automatically in `.pginf/`.",
+        "- Pages are cached automatically in the nearest `.pginf/` found walking up",
+        "  from the current directory (created in the current directory if none).",
+        "- `--cache-dir PATH` / `PGINF_CACHE_DIR`: use an explicit cache root.",
+        "- `--user-cache` / `PGINF_USER_CACHE=1`: share one per-user cache (`~/.cache/pginf`).",
         "- `--refresh`: refetch and overwrite cache.",
         "- `--no-cache`: skip cache read/write.",
+        "- `--cache-compression none|gzip|zstd`: codec for newly cached pages.",
+        "- `--cache-backend file|sqlite`: cache storage backend (or `\"backend\"` in `.pginf/config.json`).",
+        "- `--cache-history N`: previous snapshots kept per page (default 5).",
+        "- `--offline` (or `PGINF_OFFLINE=1`, or `\"offline\": true` in `.pginf/config.json`):",
+        "  serve from cache only; a miss is an error.",
+        "- Failed fetches (404, DNS errors, ...) are remembered for `--failure-ttl SECS`",
+        "  (default 300) and reported as cached failures; `--refresh` retries.",
+        "- `--normalize RULES` (or `PGINF_NORMALIZE`): URL normalization for cache keys",
+        "  and links: `sort-query`, `strip-tracking`, `remove-empty-params`,",
+        "  `trailing-slash`, `index-html`, `decode-unreserved`, or `all`.",
+        "- `config.json` in the cache root: `{\"tracking_params\": [\"ref\", \"pk_*\"]}`",
+        "  extends the tracking parameters dropped by `strip-tracking`. An invalid",
+        "  file is an error for every command except `help` and `install`.",
         "",
         "## Topics",
         "",
@@ -54,6 +76,8 @@ fn general_help() -> String {
         "- `pginf help json`",
         "- `pginf help text`",
         "- `pginf help http`",
+        "- `pginf help diff`",
+        "- `pginf help cache`",
         "- `pginf help tool`",
     ]
     .join("\n")
@@ -71,7 +95,12 @@ fn fetch_help() -> String {
         "- HTTP status code",
         "- response headers",
         "- duration in ms",
-        "- whether result came from cache",
+        "- whether result came from cache, and the cache root used",
+        "",
+        "Several URLs can be passed at once. With `--offline`, URLs missing from",
+        "the cache are listed under `Cache Misses`; other per-URL failures are listed",
+        "under `Errors` while the remaining URLs are still reported. Either makes the",
+        "command exit non-zero.",
         "",
         "## Examples",
         "",
@@ -79,6 +108,7 @@ fn fetch_help() -> String {
         "- `pginf fetch https://example.com --json`",
         "- `pginf fetch https://example.com --refresh`",
         "- `pginf fetch https://example.com --no-cache`",
+        "- `pginf --offline fetch https://example.com/a https://example.com/b`",
     ]
     .join("\n")
 }
@@ -92,20 +122,45 @@ fn links_help() -> String {
         "## What It Returns",
         "",
         "- processed links with raw and absolute URLs",
-        "- internal links grouped by first path segment",
+        "- document base from `<base href>`, used to resolve relative links",
+        "- page region of each link (nav, footer, main, ...) and per-region counts",
+        "- CSS selector path per link and groups by generalized selector (repeating listings)",
+        "- source element and attribute of each link (`a[href]`, `img[srcset]`, ...)",
+        "- internal links grouped by first path segment, with anchor text samples and stats",
+        "- URL templates with typed segments (`/news/{year}/{month}/{slug}-{id}.html`)",
+        "- query parameters with value types and pagination/sort/filter/session/tracking flags",
+        "- pagination scheme, next/prev URLs, and page parameter",
+        "- links per host (same host, subdomains, external) and top external domains",
+        "- locales from `/en/`-style path prefixes and language subdomains (collapsed when grouping);",
+        "  a bare code like `/id/` needs a region, a second locale, or a matching hreflang",
+        "- `<link rel=\"alternate\" hreflang>` alternates",
+        "- rel tokens (nofollow, sponsored, ugc, ...) and target/hreflang/type/download/title per link, with counts",
         "- path depth distribution",
         "- sample URLs per section",
         "- utility URLs (privacy, terms, feeds, etc.)",
         "",
+        "Counts, sections, hosts, and regions describe the links left after filtering.",
+        "",
         "## Flags",
         "",
-        "- `--filter all|internal|external`: select links to show",
+        "- `--filter all|internal|external|same-host|subdomain`: select links to show",
+        "- `--filter page|image|stylesheet|script|font|media|document|archive|feed|mailto|tel|javascript|other`:",
+        "  only links of one kind (from scheme, `type` attribute, element, and extension)",
+        "- `--element a,link,area,iframe,img,source,form,script,data`: only links from these elements",
+        "- `--region nav,header,footer,aside,main,article,breadcrumb,other`: only links in these page regions",
+        "  (the nearest landmark; `main` also keeps `article` links)",
+        "- `--follow-next N`: walk up to N next pages and merge their links (stops at the first failing page)",
+        "- `--strip-tracking`: drop tracking query parameters (`utm_*`, `fbclid`, config extras)",
+        "- `--dedupe`: one row per distinct URL with occurrence count, anchor texts, and first position",
         "- `--format text|json|toon`: output format",
         "",
         "## Examples",
         "",
         "- `pginf links https://example.com`",
         "- `pginf links https://example.com --filter internal`",
+        "- `pginf links https://example.com --element img,source`",
+        "- `pginf links https://example.com --region main,article`",
+        "- `pginf links https://example.com/blog --follow-next 3`",
         "- `pginf links https://example.com --format toon`",
     ]
     .join("\n")
@@ -121,6 +176,7 @@ fn meta_help() -> String {
         "",
         "- title, lang",
         "- high-signal meta tags (description, robots, og:type, article:section, etc.)",
+        "- hreflang alternates (`<link rel=\"alternate\" hreflang>`)",
         "",
         "## Examples",
         "",
@@ -170,6 +226,32 @@ fn text_help() -> String {
     .join("\n")
 }
 
+fn contacts_help() -> String {
+    [
+        "# `pginf contacts`",
+        "",
+        "Extract contact details from a page.",
+        "",
+        "## What It Returns",
+        "",
+        "- emails from `mailto:` links, query (`?subject=`) dropped",
+        "- phone numbers from `tel:` links as `+` and digits",
+        "- social profiles (X/Twitter, Facebook, LinkedIn, Telegram, YouTube,",
+        "  GitHub, Instagram, VK) normalized to handles; share and post links are skipped",
+        "- profiles listed in JSON-LD `sameAs`",
+        "",
+        "## Flags",
+        "",
+        "- `--format text|json|toon`: output format",
+        "",
+        "## Examples",
+        "",
+        "- `pginf contacts https://example.com`",
+        "- `pginf contacts https://example.com --format json`",
+    ]
+    .join("\n")
+}
+
 fn http_help() -> String {
     [
         "# `pginf http`",
@@ -197,6 +279,72 @@ fn http_help() -> String {
     .join("\n")
 }
 
+fn diff_help() -> String {
+    [
+        "# `pginf diff`",
+        "",
+        "Compare two cached snapshots of a page. Refetching a cached page keeps",
+        "the previous version as a snapshot (`--cache-history N`, default 5).",
+        "",
+        "## What It Compares",
+        "",
+        "- status, final URL, title, lang",
+        "- response headers (volatile ones such as `date` and `set-cookie` are skipped)",
+        "- meta tags, link set, headings",
+        "- extracted text lines and length",
+        "",
+        "## Flags",
+        "",
+        "- `--from N`: older snapshot, counted back from the newest (default 1)",
+        "- `--to N`: newer snapshot (default 0, the current entry)",
+        "- `--refresh`: refetch first, then compare against the previous snapshot",
+        "- `--format text|json|toon`: output format",
+        "",
+        "## Examples",
+        "",
+        "- `pginf diff https://example.com --refresh`",
+        "- `pginf diff https://example.com --from 3 --format json`",
+    ]
+    .join("\n")
+}
+
+fn cache_help() -> String {
+    [
+        "# `pginf cache`",
+        "",
+        "Inspect and maintain the page cache in `.pginf/`.",
+        "",
+        "## Subcommands",
+        "",
+        "- `pginf cache stats`: entry and snapshot counts, sizes, and space saved",
+        "- `pginf cache migrate --compression none|gzip|zstd`: rewrite stored bodies, history included",
+        "- `pginf cache list [--domain D] [--status N] [--limit N]`: cached pages, newest first",
+        "- `pginf cache export --warc out.warc.gz`: write cached pages as WARC records",
+        "- `pginf cache import crawl.warc.gz`: load HTML responses from a WARC file",
+        "",
+        "## Backends",
+        "",
+        "- `--cache-backend file` (default): one directory per page under `.pginf/pages/`",
+        "- `--cache-backend sqlite`: single indexed `.pginf/cache.sqlite` file;",
+        "  requires a build with `--features sqlite`, otherwise selecting it is an error",
+        "- `\"backend\": \"sqlite\"` in `.pginf/config.json` selects it by default",
+        "",
+        "## Compression",
+        "",
+        "- `--cache-compression none|gzip|zstd` picks the codec for newly cached pages",
+        "- cached pages load transparently whichever codec they were stored with",
+        "",
+        "## Examples",
+        "",
+        "- `pginf cache stats --json`",
+        "- `pginf cache migrate --compression zstd`",
+        "- `pginf --cache-compression zstd fetch https://example.com`",
+        "- `pginf --cache-backend sqlite cache list --domain example.com --status 404`",
+        "- `pginf cache import crawl.warc.gz && pginf --offline links https://example.com`",
+    ]
+    .join("\n")
+}
+
 fn tool_help() -> String {
     [
         "# Tool Guide",
@@ -239,7 +387,7 @@ fn unknown_help(topic: &str) -> String {
     [
         format!("# Unknown Help Topic: `{topic}`"),
         "".to_string(),
-        "Available topics: `fetch`, `links`, `meta`, `json`, `text`, `http`, `tool`".to_string(),
+        "Available topics: `fetch`, `links`, `meta`, `json`, `text`, `contacts`, `http`, `diff`, `cache`, `tool`".to_string(),
     ]
     .join("\n")
 }
@@ -267,6 +415,13 @@ mod tests {
         assert!(help.contains("HTTP metadata"));
     }
 
+    #[test]
+    fn contacts_help_mentions_same_as() {
+        let help = render(Some("contacts"));
+        assert!(help.contains("sameAs"));
+        assert!(render(None).contains("pginf contacts"));
+    }
+
     #[test]
     fn unknown_topic_returns_suggestions() {
         let help = render(Some("nonexistent"));
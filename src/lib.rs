@@ -1,24 +1,39 @@
 pub mod analyzer;
 pub mod cache;
 pub mod client;
+pub mod config;
 pub mod help;
 pub mod html;
 pub mod http_display;
+pub mod normalize;
 pub mod output;
 
 pub use analyzer::HeadingsVerbosity;
 pub use analyzer::MetaVerbosity;
+pub use analyzer::PageDiff;
 pub use analyzer::PageInfo;
+pub use analyzer::contacts::{
+    Contacts, ContactsOutput, SocialNetwork, SocialProfile,
+};
 pub use analyzer::date_kind::DateKind;
 pub use analyzer::link::{
-    Link, LinkFilter, LinkGroup, LinkOptions, LinksOutput, RawLink, extract_links,
-    extract_raw_links, extract_registered_domain,
+    Link, LinkElement, LinkFilter, LinkGroup, LinkOccurrence, LinkOptions,
+    LinkScope, LinksOutput, LinksQuery, RawLink, SelectorGroup, dedupe_links,
+    document_base, extract_links, extract_links_with, extract_raw_links,
+    extract_registered_domain, selector_groups,
 };
+pub use analyzer::link_kind::LinkKind;
+pub use analyzer::locale::HreflangAlternate;
 pub use analyzer::meta_tag::{MetaOutput, MetaTag};
+pub use analyzer::query_params::{ParamRole, ParamValueType, QueryParam};
+pub use analyzer::region::LinkRegion;
+pub use analyzer::rel::RelToken;
 pub use analyzer::text::TextOutput;
-pub use analyzer::url_facts::UrlFacts;
+pub use analyzer::url_facts::{AnchorStats, HostCount, UrlFacts};
+pub use analyzer::url_template::UrlTemplate;
 pub use client::FetchResult;
 pub use client::PageClient;
+pub use normalize::{NormalizePolicy, NormalizeRule, TrackingParams};
 pub use output::{OutputFormat, RenderOutput};
 
 pub use dom_content_extraction;
@@ -3,9 +3,11 @@ use std::error::Error;
 mod analyzer;
 mod cache;
 mod client;
+mod config;
 mod help;
 mod html;
 mod http_display;
+mod normalize;
 mod output;
 mod resolve;
 mod skills;
@@ -30,6 +32,63 @@ struct Cli {
     /// Request timeout in seconds
     #[arg(long, global = true)]
     timeout: Option<u64>,
+    /// Compression for newly cached page bodies: none, gzip, or zstd
+    #[arg(long, global = true, default_value = "none", value_parser = ["none", "gzip", "zstd"])]
+    cache_compression: String,
+    /// Cache storage backend: file or sqlite (default: `backend` in
+    /// config.json, else file)
+    #[arg(long, global = true, value_parser = ["file", "sqlite"])]
+    cache_backend: Option<String>,
+    /// Previous snapshots to keep per cached page (0 disables history)
+    #[arg(long, global = true, default_value_t = cache::DEFAULT_HISTORY_LIMIT)]
+    cache_history: usize,
+    /// Seconds to remember failed fetches before retrying (0 disables)
+    #[arg(long, global = true, default_value_t = cache::DEFAULT_FAILURE_TTL_SECS)]
+    failure_ttl: u64,
+    /// Serve pages from cache only and never touch the network (overrides
+    /// `offline` in config.json)
+    #[arg(
+        long,
+        global = true,
+        env = "PGINF_OFFLINE",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
+        value_parser = clap::builder::BoolishValueParser::new()
+    )]
+    offline: Option<bool>,
+    /// Cache root directory (default: nearest `.pginf/` up from the current dir)
+    #[arg(long, global = true, env = "PGINF_CACHE_DIR")]
+    cache_dir: Option<std::path::PathBuf>,
+    /// Use the per-user cache directory (e.g. ~/.cache/pginf)
+    #[arg(
+        long,
+        global = true,
+        env = "PGINF_USER_CACHE",
+        value_parser = clap::builder::BoolishValueParser::new()
+    )]
+    user_cache: bool,
+    /// Comma-separated URL normalization rules for cache keys and links
+    #[arg(
+        long,
+        global = true,
+        env = "PGINF_NORMALIZE",
+        value_delimiter = ',',
+        value_parser = [
+            "all",
+            "sort-query",
+            "strip-tracking",
+            "remove-empty-params",
+            "trailing-slash",
+            "index-html",
+            "decode-unreserved",
+        ]
+    )]
+    normalize: Vec<String>,
+    /// Settings from `config.json` in the cache root, loaded by `run`
+    /// (see `load_config`).
+    #[arg(skip)]
+    config: config::Config,
 }
 
 #[derive(Subcommand, Debug)]
@@ -41,8 +100,9 @@ enum Commands {
     },
     /// Fetch page, cache it, print HTTP metadata
     Fetch {
-        /// URL to fetch
-        url: String,
+        /// URLs to fetch
+        #[arg(required = true)]
+        urls: Vec<String>,
         /// Output as JSON
         #[arg(long)]
         json: bool,
@@ -57,9 +117,49 @@ enum Commands {
     Links {
         /// URL to analyze
         url: String,
-        /// Link filter: all, internal, or external
-        #[arg(long, default_value = "all", value_parser = ["all", "internal", "external"])]
+        /// Link filter: all, internal, external, same-host, subdomain, or a
+        /// link kind (page, image, stylesheet, script, font, media, document,
+        /// archive, feed, mailto, tel, javascript, other)
+        #[arg(
+            long,
+            default_value = "all",
+            value_parser = [
+                "all", "internal", "external", "same-host", "subdomain", "page",
+                "image", "stylesheet", "script", "font", "media", "document",
+                "archive", "feed", "mailto", "tel", "javascript", "other",
+            ]
+        )]
         filter: String,
+        /// Comma-separated element kinds to show (default: all)
+        #[arg(
+            long,
+            value_delimiter = ',',
+            value_parser = [
+                "a", "link", "area", "iframe", "img", "source", "form", "script",
+                "data",
+            ]
+        )]
+        element: Vec<String>,
+        /// Comma-separated page regions to show (default: all); `main` also
+        /// keeps `article` links
+        #[arg(
+            long,
+            value_delimiter = ',',
+            value_parser = [
+                "nav", "header", "footer", "aside", "main", "article", "breadcrumb",
+                "other",
+            ]
+        )]
+        region: Vec<String>,
+        /// Follow up to N "next page" links and merge their links
+        #[arg(long, value_name = "N", default_value_t = 0)]
+        follow_next: usize,
+        /// Show one row per distinct URL with occurrence count and anchor texts
+        #[arg(long)]
+        dedupe: bool,
+        /// Drop tracking query parameters (built-in list plus config extras)
+        #[arg(long)]
+        strip_tracking: bool,
         /// Output format: text, json, or toon
         #[arg(long, default_value = "text", value_parser = ["text", "json", "toon"])]
         format: String,
@@ -115,6 +215,20 @@ enum Commands {
         #[arg(long)]
         refresh: bool,
     },
+    /// Extract emails, phone numbers, and social profiles
+    Contacts {
+        /// URL to analyze
+        url: String,
+        /// Output format: text, json, or toon
+        #[arg(long, default_value = "text", value_parser = ["text", "json", "toon"])]
+        format: String,
+        /// Ignore cache and do not write fetched page to cache
+        #[arg(long, conflicts_with = "refresh")]
+        no_cache: bool,
+        /// Refetch page and overwrite existing cache entry
+        #[arg(long)]
+        refresh: bool,
+    },
     /// Show page headings (h1–h6)
     Headings {
         /// URL to analyze
@@ -153,6 +267,28 @@ enum Commands {
         #[arg(long)]
         refresh: bool,
     },
+    /// Compare two cached snapshots of a page
+    Diff {
+        /// URL to compare
+        url: String,
+        /// Older snapshot, counted back from the newest (0)
+        #[arg(long, default_value_t = 1)]
+        from: usize,
+        /// Newer snapshot, counted back from the newest (0)
+        #[arg(long, default_value_t = 0)]
+        to: usize,
+        /// Output format: text, json, or toon
+        #[arg(long, default_value = "text", value_parser = ["text", "json", "toon"])]
+        format: String,
+        /// Refetch page first so the newest snapshot is current
+        #[arg(long)]
+        refresh: bool,
+    },
+    /// Inspect and maintain the page cache
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommand,
+    },
     /// Install pginf skill files for AI coding agents
     Install {
         #[command(subcommand)]
@@ -160,6 +296,48 @@ enum Commands {
     },
 }
 
+#[derive(Subcommand, Debug)]
+enum CacheCommand {
+    /// Show entry count and space used by cached page bodies
+    Stats {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Rewrite cached page bodies with a different compression
+    Migrate {
+        /// Target compression: none, gzip, or zstd
+        #[arg(long, value_parser = ["none", "gzip", "zstd"])]
+        compression: String,
+    },
+    /// List cached pages, newest first
+    List {
+        /// Only entries whose registered domain matches
+        #[arg(long)]
+        domain: Option<String>,
+        /// Only entries with this HTTP status
+        #[arg(long)]
+        status: Option<u16>,
+        /// Maximum number of entries to show
+        #[arg(long)]
+        limit: Option<usize>,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Write cached pages to a WARC file
+    Export {
+        /// Output path; gzip-compressed when it ends in .gz
+        #[arg(long)]
+        warc: std::path::PathBuf,
+    },
+    /// Load HTML responses from a WARC file (plain or .gz) into the cache
+    Import {
+        /// WARC file to read
+        path: std::path::PathBuf,
+    },
+}
+
 #[derive(Subcommand, Debug)]
 enum InstallCommand {
     /// Install skill files
@@ -177,10 +355,22 @@ enum SkillsTarget {
     Global,
 }
 
+/// Runs the CLI and prints any error with its message. Returning the
+/// `Result` from `main` would print its `Debug` form instead, which wraps
+/// message-only errors such as the batch `fetch` summary in quotes.
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn Error>> {
-    let cli = Cli::parse();
+async fn main() -> std::process::ExitCode {
+    match run(Cli::parse()).await {
+        Ok(()) => std::process::ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::ExitCode::FAILURE
+        }
+    }
+}
 
+async fn run(mut cli: Cli) -> Result<(), Box<dyn Error>> {
+    cli.config = load_config(&cli)?;
     let mut page_client = client::PageClient::builder();
     if let Some(ref proxy) = cli.proxy {
         page_client = page_client.proxy(proxy)?;
@@ -200,37 +390,109 @@ async fn main() -> Result<(), Box<dyn Error>> {
             println!("{}", help::render(topic.as_deref()));
         }
         Commands::Fetch {
-            url,
+            urls,
             json,
             no_cache,
             refresh,
         } => {
-            let resolved =
-                resolve::resolve_page(url, &page_client, *no_cache, *refresh)
-                    .await?;
+            let config = cache_config(&cli, *no_cache, *refresh);
+            if let [url] = urls.as_slice() {
+                let resolved =
+                    resolve::resolve_page(url, &page_client, &config).await?;
+                if *json {
+                    println!("{}", format_fetch_json(&resolved));
+                } else {
+                    println!("{}", format_fetch_markdown(&resolved));
+                }
+                return Ok(());
+            }
+
+            // One failing URL must not discard the others: report each
+            // error and exit non-zero at the end.
+            let mut resolved_pages = Vec::new();
+            let mut misses = Vec::new();
+            let mut failures = Vec::new();
+            for url in urls {
+                match resolve::resolve_page(url, &page_client, &config).await {
+                    Ok(resolved) => resolved_pages.push(resolved),
+                    Err(client::ClientError::NotCached { url }) => misses.push(url),
+                    Err(e) => failures.push((url.clone(), e.to_string())),
+                }
+            }
             if *json {
-                println!("{}", format_fetch_json(&resolved));
+                println!(
+                    "{}",
+                    format_fetch_batch_json(&resolved_pages, &misses, &failures)
+                );
             } else {
-                println!("{}", format_fetch_markdown(&resolved));
+                println!(
+                    "{}",
+                    format_fetch_batch_markdown(
+                        &resolved_pages,
+                        &misses,
+                        &failures
+                    )
+                );
+            }
+            if let Some(error) =
+                fetch_batch_error(urls.len(), misses.len(), failures.len())
+            {
+                return Err(error.into());
             }
         }
         Commands::Links {
             url,
             filter,
+            element,
+            region,
+            follow_next,
+            dedupe,
+            strip_tracking,
             format,
             no_cache,
             refresh,
         } => {
+            let config = cache_config(&cli, *no_cache, *refresh);
+            let options = analyzer::link::LinkOptions {
+                strip_tracking_params: *strip_tracking,
+                ..link_options(&cli)
+            };
             let resolved =
-                resolve::resolve_page(url, &page_client, *no_cache, *refresh)
-                    .await?;
-            let page =
-                analyzer::PageInfo::from_fetch_result(&resolved.fetch_result)?;
-            let filter = analyzer::link::LinkFilter::parse(filter)
-                .unwrap_or(analyzer::link::LinkFilter::All);
+                resolve::resolve_page(url, &page_client, &config).await?;
+            let mut page = analyzer::PageInfo::from_fetch_result_with(
+                &resolved.fetch_result,
+                &options,
+            )?;
+            let followed = if *follow_next > 0 {
+                follow_next_pages(
+                    &options,
+                    &mut page,
+                    *follow_next,
+                    &page_client,
+                    &config,
+                )
+                .await
+            } else {
+                Vec::new()
+            };
+            let query = analyzer::link::LinksQuery {
+                filter: analyzer::link::LinkFilter::parse(filter)
+                    .unwrap_or(analyzer::link::LinkFilter::All),
+                elements: element
+                    .iter()
+                    .filter_map(|kind| analyzer::link::LinkElement::parse(kind))
+                    .collect(),
+                regions: region
+                    .iter()
+                    .filter_map(|name| analyzer::region::LinkRegion::parse(name))
+                    .collect(),
+                dedupe: *dedupe,
+            };
             let format = output::OutputFormat::parse(format)
                 .unwrap_or(output::OutputFormat::Text);
-            println!("{}", page.links_output(filter).render(format));
+            let mut output = page.links_output_with(&query);
+            output.followed = followed;
+            println!("{}", output.render(format));
         }
         Commands::Meta {
             url,
@@ -239,11 +501,16 @@ async fn main() -> Result<(), Box<dyn Error>> {
             no_cache,
             refresh,
         } => {
-            let resolved =
-                resolve::resolve_page(url, &page_client, *no_cache, *refresh)
-                    .await?;
-            let page =
-                analyzer::PageInfo::from_fetch_result(&resolved.fetch_result)?;
+            let resolved = resolve::resolve_page(
+                url,
+                &page_client,
+                &cache_config(&cli, *no_cache, *refresh),
+            )
+            .await?;
+            let page = analyzer::PageInfo::from_fetch_result_with(
+                &resolved.fetch_result,
+                &link_options(&cli),
+            )?;
             let verbosity = analyzer::MetaVerbosity::parse(verbosity)
                 .unwrap_or(analyzer::MetaVerbosity::Main);
             let format = output::OutputFormat::parse(format)
@@ -256,11 +523,16 @@ async fn main() -> Result<(), Box<dyn Error>> {
             no_cache,
             refresh,
         } => {
-            let resolved =
-                resolve::resolve_page(url, &page_client, *no_cache, *refresh)
-                    .await?;
-            let page =
-                analyzer::PageInfo::from_fetch_result(&resolved.fetch_result)?;
+            let resolved = resolve::resolve_page(
+                url,
+                &page_client,
+                &cache_config(&cli, *no_cache, *refresh),
+            )
+            .await?;
+            let page = analyzer::PageInfo::from_fetch_result_with(
+                &resolved.fetch_result,
+                &link_options(&cli),
+            )?;
             if *json {
                 println!("{}", page.json_data_json());
             } else {
@@ -273,15 +545,40 @@ async fn main() -> Result<(), Box<dyn Error>> {
             no_cache,
             refresh,
         } => {
-            let resolved =
-                resolve::resolve_page(url, &page_client, *no_cache, *refresh)
-                    .await?;
-            let page =
-                analyzer::PageInfo::from_fetch_result(&resolved.fetch_result)?;
+            let resolved = resolve::resolve_page(
+                url,
+                &page_client,
+                &cache_config(&cli, *no_cache, *refresh),
+            )
+            .await?;
+            let page = analyzer::PageInfo::from_fetch_result_with(
+                &resolved.fetch_result,
+                &link_options(&cli),
+            )?;
             let format = output::OutputFormat::parse(format)
                 .unwrap_or(output::OutputFormat::Text);
             println!("{}", page.text_output().render(format));
         }
+        Commands::Contacts {
+            url,
+            format,
+            no_cache,
+            refresh,
+        } => {
+            let resolved = resolve::resolve_page(
+                url,
+                &page_client,
+                &cache_config(&cli, *no_cache, *refresh),
+            )
+            .await?;
+            let page = analyzer::PageInfo::from_fetch_result_with(
+                &resolved.fetch_result,
+                &link_options(&cli),
+            )?;
+            let format = output::OutputFormat::parse(format)
+                .unwrap_or(output::OutputFormat::Text);
+            println!("{}", page.contacts_output().render(format));
+        }
         Commands::Headings {
             url,
             verbosity,
@@ -289,11 +586,16 @@ async fn main() -> Result<(), Box<dyn Error>> {
             no_cache,
             refresh,
         } => {
-            let resolved =
-                resolve::resolve_page(url, &page_client, *no_cache, *refresh)
-                    .await?;
-            let page =
-                analyzer::PageInfo::from_fetch_result(&resolved.fetch_result)?;
+            let resolved = resolve::resolve_page(
+                url,
+                &page_client,
+                &cache_config(&cli, *no_cache, *refresh),
+            )
+            .await?;
+            let page = analyzer::PageInfo::from_fetch_result_with(
+                &resolved.fetch_result,
+                &link_options(&cli),
+            )?;
             let verbosity = analyzer::HeadingsVerbosity::parse(verbosity)
                 .unwrap_or(analyzer::HeadingsVerbosity::Main);
             let format = output::OutputFormat::parse(format)
@@ -301,6 +603,12 @@ async fn main() -> Result<(), Box<dyn Error>> {
             println!("{}", page.headings_output(verbosity).render(format));
         }
         Commands::Http { url } => {
+            if offline(&cli) {
+                return Err(
+                    "`pginf http` always fetches and is unavailable with --offline"
+                        .into(),
+                );
+            }
             let parsed = url::Url::parse(url)?;
             match http_display::retrieve_page(&parsed, &page_client).await {
                 Ok(transaction) => {
@@ -327,9 +635,12 @@ async fn main() -> Result<(), Box<dyn Error>> {
             no_cache,
             refresh,
         } => {
-            let resolved =
-                resolve::resolve_page(url, &page_client, *no_cache, *refresh)
-                    .await?;
+            let resolved = resolve::resolve_page(
+                url,
+                &page_client,
+                &cache_config(&cli, *no_cache, *refresh),
+            )
+            .await?;
             match selector {
                 None => {
                     println!("{}", resolved.fetch_result.body);
@@ -362,6 +673,109 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 }
             }
         }
+        Commands::Diff {
+            url,
+            from,
+            to,
+            format,
+            refresh,
+        } => {
+            let config = cache_config(&cli, false, *refresh);
+            let cache = cache::open(config.clone())?;
+            let final_url = if *refresh {
+                resolve::resolve_page(url, &page_client, &config)
+                    .await?
+                    .fetch_result
+                    .final_url
+            } else {
+                stored_final_url(cache.as_ref(), url)?
+            };
+            let snapshots =
+                cache.snapshots(&cache.key_for_final_url(&final_url)?)?;
+            let snapshot = |index: usize| {
+                snapshots
+                    .get(index)
+                    .ok_or(cache::CacheError::MissingSnapshot {
+                        url: final_url.clone(),
+                        index,
+                        available: snapshots.len(),
+                    })
+            };
+            let diff =
+                analyzer::PageDiff::between(snapshot(*from)?, snapshot(*to)?)?;
+            let format = output::OutputFormat::parse(format)
+                .unwrap_or(output::OutputFormat::Text);
+            println!("{}", diff.render(format));
+        }
+        Commands::Cache { command } => match command {
+            CacheCommand::Stats { json } => {
+                let config = cache_config(&cli, false, false);
+                let stats = cache::open(config.clone())?.stats()?;
+                if *json {
+                    println!("{}", format_cache_stats_json(&stats, &config));
+                } else {
+                    println!("{}", format_cache_stats_markdown(&stats, &config));
+                }
+            }
+            CacheCommand::Migrate { compression } => {
+                let compression =
+                    cache::Compression::parse(compression).unwrap_or_default();
+                let config = cache::CacheConfig {
+                    compression,
+                    ..cache_config(&cli, false, false)
+                };
+                let cache = cache::open(config.clone())?;
+                cache.init()?;
+                let migrated = cache.migrate(compression)?;
+                println!(
+                    "Migrated {migrated} page bodies to {}\n",
+                    compression.as_str()
+                );
+                println!(
+                    "{}",
+                    format_cache_stats_markdown(&cache.stats()?, &config)
+                );
+            }
+            CacheCommand::List {
+                domain,
+                status,
+                limit,
+                json,
+            } => {
+                let query = cache::CacheQuery {
+                    domain: domain.clone(),
+                    status: *status,
+                    limit: *limit,
+                };
+                let entries =
+                    cache::open(cache_config(&cli, false, false))?.list(&query)?;
+                if *json {
+                    println!("{}", format_cache_list_json(&entries));
+                } else {
+                    println!("{}", format_cache_list_markdown(&entries));
+                }
+            }
+            CacheCommand::Export { warc } => {
+                let cache = cache::open(cache_config(&cli, false, false))?;
+                let exported = cache::export_to_path(cache.as_ref(), warc)?;
+                println!("Exported {exported} pages to {}", warc.display());
+            }
+            CacheCommand::Import { path } => {
+                let config = cache_config(&cli, false, false);
+                let cache = cache::open(config.clone())?;
+                cache.init()?;
+                let summary = cache::import_from_path(cache.as_ref(), path)?;
+                println!(
+                    "Imported {} pages into {} ({} non-HTML and {} unreadable \
+                     responses, {} malformed records skipped)",
+                    summary.imported,
+                    config.root_dir.display(),
+                    summary.skipped_non_html,
+                    summary.skipped_unreadable,
+                    summary.skipped_malformed
+                );
+            }
+        },
         Commands::Install { command } => match command {
             InstallCommand::Skills { target } => match target {
                 SkillsTarget::Local => match skills::install_local() {
@@ -379,6 +793,158 @@ async fn main() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+/// Walks up to `limit` pages through each page's detected next link and
+/// merges their links into `page`. The first entry is `page` itself. A page
+/// that fails to load ends the walk and is listed with its error.
+async fn follow_next_pages(
+    options: &analyzer::link::LinkOptions,
+    page: &mut analyzer::PageInfo,
+    limit: usize,
+    client: &client::PageClient,
+    config: &cache::CacheConfig,
+) -> Vec<analyzer::pagination::FollowedPage> {
+    let mut followed = vec![analyzer::pagination::FollowedPage {
+        url: page.final_url.clone(),
+        links: page.links.len(),
+        new_links: page.links.len(),
+        error: None,
+    }];
+    let mut visited = vec![page.final_url.clone()];
+    let mut next = page.pagination.as_ref().and_then(|p| p.next.clone());
+    while let Some(url) = next.take() {
+        if followed.len() > limit || visited.contains(&url) {
+            break;
+        }
+        let next_page = match load_page(&url, options, client, config).await {
+            Ok(next_page) => next_page,
+            Err(e) => {
+                followed.push(analyzer::pagination::FollowedPage {
+                    url,
+                    links: 0,
+                    new_links: 0,
+                    error: Some(e.to_string()),
+                });
+                break;
+            }
+        };
+        let new_links = page.merge_links(&next_page);
+        followed.push(analyzer::pagination::FollowedPage {
+            url: next_page.final_url.clone(),
+            links: next_page.links.len(),
+            new_links,
+            error: None,
+        });
+        visited.push(url);
+        visited.push(next_page.final_url.clone());
+        next = next_page.pagination.and_then(|p| p.next);
+    }
+    followed
+}
+
+async fn load_page(
+    url: &str,
+    options: &analyzer::link::LinkOptions,
+    client: &client::PageClient,
+    config: &cache::CacheConfig,
+) -> Result<analyzer::PageInfo, Box<dyn Error>> {
+    let resolved = resolve::resolve_page(url, client, config).await?;
+    Ok(analyzer::PageInfo::from_fetch_result_with(
+        &resolved.fetch_result,
+        options,
+    )?)
+}
+
+fn cache_root(cli: &Cli) -> std::path::PathBuf {
+    cache::locate_root(
+        cli.cache_dir.as_deref(),
+        cli.user_cache,
+        &std::env::current_dir().unwrap_or_default(),
+    )
+}
+
+/// Reads `config.json` from the cache root. `help` and `install` never read
+/// it; for everything else an invalid file is an error, so a typo cannot
+/// silently turn off settings such as `offline`.
+fn load_config(cli: &Cli) -> Result<config::Config, config::ConfigError> {
+    if matches!(
+        cli.command,
+        Commands::Help { .. } | Commands::Install { .. }
+    ) {
+        return Ok(config::Config::default());
+    }
+    config::Config::load(&cache_root(cli))
+}
+
+/// `--offline` or `PGINF_OFFLINE` when given, else `offline` from config.json.
+fn offline(cli: &Cli) -> bool {
+    cli.offline.or(cli.config.offline).unwrap_or_default()
+}
+
+/// Final URL of the cached entry for `url`. Entries are keyed by final URL,
+/// so a redirected page is found through the `input_url` it was fetched as.
+fn stored_final_url(
+    cache: &dyn cache::Cache,
+    url: &str,
+) -> Result<String, cache::CacheError> {
+    let key = cache.key_for_final_url(url)?;
+    if let Some(page) = cache.load(&key)? {
+        return Ok(page.fetch.final_url);
+    }
+    for fetch in cache.list(&cache::CacheQuery::default())? {
+        if cache.key_for_final_url(&fetch.input_url)?.hash == key.hash {
+            return Ok(fetch.final_url);
+        }
+    }
+    Ok(url.to_string())
+}
+
+/// `--cache-backend` when given, else `backend` from config.json.
+fn cache_backend(cli: &Cli) -> cache::CacheBackend {
+    cli.cache_backend
+        .as_deref()
+        .and_then(cache::CacheBackend::parse)
+        .or(cli.config.backend)
+        .unwrap_or_default()
+}
+
+fn cache_config(cli: &Cli, no_cache: bool, refresh: bool) -> cache::CacheConfig {
+    cache::CacheConfig {
+        root_dir: cache_root(cli),
+        enabled: !no_cache,
+        refresh,
+        compression: cache::Compression::parse(&cli.cache_compression)
+            .unwrap_or_default(),
+        backend: cache_backend(cli),
+        history_limit: cli.cache_history,
+        offline: offline(cli),
+        normalization: normalize_policy(cli),
+        failure_ttl_secs: cli.failure_ttl,
+    }
+}
+
+fn normalize_policy(cli: &Cli) -> normalize::NormalizePolicy {
+    let mut policy = if cli.normalize.iter().any(|rule| rule == "all") {
+        normalize::NormalizePolicy::all()
+    } else {
+        let rules: Vec<_> = cli
+            .normalize
+            .iter()
+            .filter_map(|rule| normalize::NormalizeRule::parse(rule))
+            .collect();
+        normalize::NormalizePolicy::from_rules(&rules)
+    };
+    policy.tracking_params = cli.config.tracking_params();
+    policy
+}
+
+fn link_options(cli: &Cli) -> analyzer::link::LinkOptions {
+    analyzer::link::LinkOptions {
+        normalize: true,
+        url_policy: normalize_policy(cli),
+        ..Default::default()
+    }
+}
+
 fn format_fetch_markdown(resolved: &resolve::ResolveOutput) -> String {
     let r = &resolved.fetch_result;
     let mut out = String::new();
@@ -386,7 +952,14 @@ fn format_fetch_markdown(resolved: &resolve::ResolveOutput) -> String {
     out.push_str(&format!("- **Input URL:** {}\n", r.input_url));
     out.push_str(&format!("- **Final URL:** {}\n", r.final_url));
     out.push_str(&format!("- **Status:** {}\n", r.status));
-    out.push_str(&format!("- **Duration:** {}ms\n", r.duration_ms));
+    if resolved.from_cache {
+        out.push_str(&format!(
+            "- **Duration:** {}ms (original fetch)\n",
+            r.duration_ms
+        ));
+    } else {
+        out.push_str(&format!("- **Duration:** {}ms\n", r.duration_ms));
+    }
     if let Some(ref emu) = r.emulation_used {
         out.push_str(&format!("- **Emulation:** {emu}\n"));
     }
@@ -400,6 +973,15 @@ fn format_fetch_markdown(resolved: &resolve::ResolveOutput) -> String {
         "- **Cached:** {}\n",
         if resolved.from_cache { "yes" } else { "no" }
     ));
+    if let Some(ref fetched_at) = resolved.fetched_at {
+        out.push_str(&format!("- **Fetched at:** {fetched_at}\n"));
+    }
+    if let Some(ms) = resolved.cache_read_ms {
+        out.push_str(&format!("- **Cache read:** {ms}ms\n"));
+    }
+    if let Some(ref root) = resolved.cache_root {
+        out.push_str(&format!("- **Cache root:** {}\n", root.display()));
+    }
     out.push_str(&format!("- **Body size:** {} bytes\n", r.body.len()));
     if !r.headers.is_empty() {
         out.push_str("\n### Response Headers\n\n");
@@ -411,8 +993,12 @@ fn format_fetch_markdown(resolved: &resolve::ResolveOutput) -> String {
 }
 
 fn format_fetch_json(resolved: &resolve::ResolveOutput) -> String {
+    serde_json::to_string_pretty(&fetch_json_value(resolved)).unwrap_or_default()
+}
+
+fn fetch_json_value(resolved: &resolve::ResolveOutput) -> serde_json::Value {
     let r = &resolved.fetch_result;
-    let obj = serde_json::json!({
+    serde_json::json!({
         "input_url": r.input_url,
         "final_url": r.final_url,
         "status": r.status,
@@ -421,12 +1007,158 @@ fn format_fetch_json(resolved: &resolve::ResolveOutput) -> String {
         "proxy_used": r.proxy_used,
         "attempts": r.attempts,
         "cached": resolved.from_cache,
+        "fetched_at": resolved.fetched_at,
+        "cache_read_ms": resolved.cache_read_ms,
+        "cache_root": resolved.cache_root.as_ref().map(|root| root.display().to_string()),
         "body_size": r.body.len(),
         "headers": r.headers,
+    })
+}
+
+fn format_fetch_batch_markdown(
+    resolved: &[resolve::ResolveOutput],
+    misses: &[String],
+    failures: &[(String, String)],
+) -> String {
+    let mut out = resolved
+        .iter()
+        .map(format_fetch_markdown)
+        .collect::<Vec<_>>()
+        .join("\n");
+    if !misses.is_empty() {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(&format!("## Cache Misses ({})\n\n", misses.len()));
+        for url in misses {
+            out.push_str(&format!("- {url}\n"));
+        }
+    }
+    if !failures.is_empty() {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(&format!("## Errors ({})\n\n", failures.len()));
+        for (url, error) in failures {
+            out.push_str(&format!("- {url}: {error}\n"));
+        }
+    }
+    out
+}
+
+fn format_fetch_batch_json(
+    resolved: &[resolve::ResolveOutput],
+    misses: &[String],
+    failures: &[(String, String)],
+) -> String {
+    let obj = serde_json::json!({
+        "results": resolved.iter().map(fetch_json_value).collect::<Vec<_>>(),
+        "cache_misses": misses,
+        "errors": failures
+            .iter()
+            .map(|(url, error)| serde_json::json!({ "url": url, "error": error }))
+            .collect::<Vec<_>>(),
+    });
+    serde_json::to_string_pretty(&obj).unwrap_or_default()
+}
+
+/// Summary error for a batch fetch, `None` when every URL resolved.
+fn fetch_batch_error(
+    total: usize,
+    misses: usize,
+    failures: usize,
+) -> Option<String> {
+    match (misses, failures) {
+        (0, 0) => None,
+        (misses, 0) => Some(format!("{misses} of {total} URLs not in cache")),
+        (0, failures) => Some(format!("{failures} of {total} URLs failed")),
+        (misses, failures) => Some(format!(
+            "{} of {total} URLs failed or not in cache ({misses} not in cache)",
+            misses + failures
+        )),
+    }
+}
+
+fn format_cache_stats_markdown(
+    stats: &cache::CacheStats,
+    config: &cache::CacheConfig,
+) -> String {
+    let mut out = String::new();
+    out.push_str("## Cache Stats\n\n");
+    out.push_str(&format!("- **Root:** {}\n", config.root_dir.display()));
+    out.push_str(&format!("- **Backend:** {}\n", config.backend.as_str()));
+    out.push_str(&format!("- **Entries:** {}\n", stats.entries));
+    out.push_str(&format!("- **Snapshots:** {}\n", stats.snapshots));
+    out.push_str(&format!("- **Body size:** {} bytes\n", stats.body_bytes));
+    out.push_str(&format!(
+        "- **Stored size:** {} bytes\n",
+        stats.stored_bytes
+    ));
+    let saved_pct = if stats.body_bytes > 0 {
+        stats.saved_bytes() as f64 * 100.0 / stats.body_bytes as f64
+    } else {
+        0.0
+    };
+    out.push_str(&format!(
+        "- **Saved:** {} bytes ({saved_pct:.1}%)\n",
+        stats.saved_bytes()
+    ));
+    if !stats.by_compression.is_empty() {
+        let codecs: Vec<String> = stats
+            .by_compression
+            .iter()
+            .map(|(codec, count)| format!("{codec}: {count}"))
+            .collect();
+        out.push_str(&format!("- **Compression:** {}\n", codecs.join(", ")));
+    }
+    out
+}
+
+fn format_cache_stats_json(
+    stats: &cache::CacheStats,
+    config: &cache::CacheConfig,
+) -> String {
+    let obj = serde_json::json!({
+        "root": config.root_dir.display().to_string(),
+        "backend": config.backend.as_str(),
+        "entries": stats.entries,
+        "snapshots": stats.snapshots,
+        "body_bytes": stats.body_bytes,
+        "stored_bytes": stats.stored_bytes,
+        "saved_bytes": stats.saved_bytes(),
+        "by_compression": stats.by_compression,
     });
     serde_json::to_string_pretty(&obj).unwrap_or_default()
 }
 
+fn format_cache_list_markdown(entries: &[cache::CachedFetch]) -> String {
+    let mut out = String::new();
+    out.push_str(&format!("## Cached Pages ({})\n\n", entries.len()));
+    for entry in entries {
+        out.push_str(&format!(
+            "- `{}` {} (fetched at {})\n",
+            entry.status, entry.final_url, entry.fetched_at
+        ));
+    }
+    out
+}
+
+fn format_cache_list_json(entries: &[cache::CachedFetch]) -> String {
+    let items: Vec<serde_json::Value> = entries
+        .iter()
+        .map(|entry| {
+            serde_json::json!({
+                "input_url": entry.input_url,
+                "final_url": entry.final_url,
+                "domain": entry.domain(),
+                "status": entry.status,
+                "fetched_at": entry.fetched_at,
+            })
+        })
+        .collect();
+    serde_json::to_string_pretty(&items).unwrap_or_default()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -449,12 +1181,12 @@ mod tests {
             Cli::try_parse_from(["pginf", "fetch", "https://example.com"]).unwrap();
         match cli.command {
             Commands::Fetch {
-                url,
+                urls,
                 json,
                 no_cache,
                 ..
             } => {
-                assert_eq!(url, "https://example.com");
+                assert_eq!(urls, vec!["https://example.com"]);
                 assert!(!json);
                 assert!(!no_cache);
             }
@@ -556,6 +1288,279 @@ mod tests {
         }
     }
 
+    #[test]
+    fn links_accepts_filter_same_host() {
+        let cli = Cli::try_parse_from([
+            "pginf",
+            "links",
+            "https://example.com",
+            "--filter",
+            "same-host",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Links { filter, .. } => assert_eq!(filter, "same-host"),
+            _ => panic!("expected links command"),
+        }
+    }
+
+    #[test]
+    fn links_accepts_kind_filter() {
+        let cli = Cli::try_parse_from([
+            "pginf",
+            "links",
+            "https://example.com",
+            "--filter",
+            "stylesheet",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Links { filter, .. } => assert_eq!(
+                analyzer::link::LinkFilter::parse(&filter),
+                Some(analyzer::link::LinkFilter::Kind(
+                    analyzer::link_kind::LinkKind::Stylesheet
+                ))
+            ),
+            _ => panic!("expected links command"),
+        }
+    }
+
+    #[test]
+    fn links_accepts_dedupe() {
+        let cli = Cli::try_parse_from([
+            "pginf",
+            "links",
+            "https://example.com",
+            "--dedupe",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Links { dedupe, .. } => assert!(dedupe),
+            _ => panic!("expected links command"),
+        }
+    }
+
+    #[test]
+    fn links_strip_tracking_uses_config_params() {
+        let mut cli = Cli::try_parse_from([
+            "pginf",
+            "links",
+            "https://example.com",
+            "--strip-tracking",
+        ])
+        .unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Links {
+                strip_tracking: true,
+                ..
+            }
+        ));
+        cli.config.tracking_params = vec!["ref".to_string()];
+        let policy = normalize_policy(&cli);
+        assert!(policy.tracking_params.matches("ref"));
+        assert!(policy.tracking_params.matches("utm_source"));
+        assert_eq!(cache_config(&cli, false, false).normalization, policy);
+    }
+
+    #[test]
+    fn broken_config_fails_every_command_that_reads_it() {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("pginf-config-{nanos}"));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join(config::CONFIG_FILE), r#"{"tracking": []}"#)
+            .unwrap();
+        let cli = |args: &[&str]| {
+            let root = root.to_str().unwrap();
+            Cli::try_parse_from(
+                ["pginf", "--cache-dir", root].iter().chain(args).copied(),
+            )
+            .unwrap()
+        };
+
+        assert!(load_config(&cli(&["meta", "https://example.com"])).is_err());
+        assert!(load_config(&cli(&["cache", "stats"])).is_err());
+        let stripped = cli(&["links", "https://example.com", "--strip-tracking"]);
+        assert!(load_config(&stripped).is_err());
+
+        let help = cli(&["help"]);
+        assert_eq!(load_config(&help).unwrap(), config::Config::default());
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn links_accepts_element_kinds() {
+        let cli = Cli::try_parse_from([
+            "pginf",
+            "links",
+            "https://example.com",
+            "--element",
+            "a,img",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Links { element, .. } => assert_eq!(element, ["a", "img"]),
+            _ => panic!("expected links command"),
+        }
+
+        let err = Cli::try_parse_from([
+            "pginf",
+            "links",
+            "https://example.com",
+            "--element",
+            "video",
+        ])
+        .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidValue);
+    }
+
+    #[test]
+    fn links_parses_follow_next() {
+        let cli = Cli::try_parse_from([
+            "pginf",
+            "links",
+            "https://example.com/blog",
+            "--follow-next",
+            "3",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Links { follow_next, .. } => assert_eq!(follow_next, 3),
+            _ => panic!("expected links command"),
+        }
+
+        let cli =
+            Cli::try_parse_from(["pginf", "links", "https://example.com"]).unwrap();
+        match cli.command {
+            Commands::Links { follow_next, .. } => assert_eq!(follow_next, 0),
+            _ => panic!("expected links command"),
+        }
+    }
+
+    #[tokio::test]
+    async fn follow_next_keeps_merged_pages_when_a_page_fails() {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let config = cache::CacheConfig {
+            root_dir: std::env::temp_dir().join(format!("pginf-follow-{nanos}")),
+            offline: true,
+            ..Default::default()
+        };
+        let options = analyzer::link::LinkOptions::default();
+        let mut page = analyzer::PageInfo::from_fetch_result_with(
+            &client::FetchResult {
+                input_url: "http://127.0.0.1:9/list".to_string(),
+                final_url: "http://127.0.0.1:9/list".to_string(),
+                status: 200,
+                body:
+                    r#"<a href="/a">A</a><a rel="next" href="/list?page=2">2</a>"#
+                        .to_string(),
+                ..Default::default()
+            },
+            &options,
+        )
+        .unwrap();
+
+        let followed = follow_next_pages(
+            &options,
+            &mut page,
+            3,
+            &client::PageClient::builder().build(),
+            &config,
+        )
+        .await;
+        assert_eq!(followed.len(), 2);
+        assert!(followed[0].error.is_none());
+        assert_eq!(followed[1].url, "http://127.0.0.1:9/list?page=2");
+        assert!(
+            followed[1]
+                .error
+                .as_deref()
+                .unwrap()
+                .contains("not in cache")
+        );
+        assert_eq!(page.links.len(), 2);
+
+        let _ = std::fs::remove_dir_all(config.root_dir);
+    }
+
+    #[test]
+    fn diff_finds_snapshots_of_a_redirected_url() {
+        use cache::Cache;
+
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("pginf-diff-{nanos}"));
+        let cache = cache::FileCache::new(cache::CacheConfig {
+            root_dir: root.clone(),
+            ..Default::default()
+        });
+        cache.init().unwrap();
+        let input_url = "https://example.com/old";
+        for (fetched_at, body) in [("10", "<h1>v1</h1>"), ("20", "<h1>v2</h1>")] {
+            let mut page = client::FetchResult {
+                input_url: input_url.to_string(),
+                final_url: "https://example.com/new".to_string(),
+                status: 200,
+                body: body.to_string(),
+                ..Default::default()
+            }
+            .to_cached_page();
+            page.fetch.fetched_at = fetched_at.to_string();
+            cache.store(page).unwrap();
+        }
+
+        let final_url = stored_final_url(&cache, input_url).unwrap();
+        assert_eq!(final_url, "https://example.com/new");
+        let key = cache.key_for_final_url(&final_url).unwrap();
+        assert_eq!(cache.snapshots(&key).unwrap().len(), 2);
+        assert_eq!(
+            stored_final_url(&cache, "https://example.com/new").unwrap(),
+            final_url
+        );
+        assert_eq!(
+            stored_final_url(&cache, "https://example.com/gone").unwrap(),
+            "https://example.com/gone"
+        );
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn links_accepts_regions() {
+        let cli = Cli::try_parse_from([
+            "pginf",
+            "links",
+            "https://example.com",
+            "--region",
+            "main,article",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Links { region, .. } => {
+                assert_eq!(region, ["main", "article"])
+            }
+            _ => panic!("expected links command"),
+        }
+
+        let err = Cli::try_parse_from([
+            "pginf",
+            "links",
+            "https://example.com",
+            "--region",
+            "sidebar",
+        ])
+        .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidValue);
+    }
+
     #[test]
     fn links_accepts_format_json() {
         let cli = Cli::try_parse_from([
@@ -612,7 +1617,7 @@ mod tests {
             "links",
             "https://example.com",
             "--filter",
-            "same-host",
+            "sideways",
         ])
         .unwrap_err();
         assert_eq!(err.kind(), ErrorKind::InvalidValue);
@@ -822,6 +1827,25 @@ mod tests {
         assert_eq!(err.kind(), ErrorKind::UnknownArgument);
     }
 
+    #[test]
+    fn contacts_parses_url_and_format() {
+        let cli = Cli::try_parse_from([
+            "pginf",
+            "contacts",
+            "https://example.com",
+            "--format",
+            "toon",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Contacts { url, format, .. } => {
+                assert_eq!(url, "https://example.com");
+                assert_eq!(format, "toon");
+            }
+            _ => panic!("expected contacts command"),
+        }
+    }
+
     #[test]
     fn html_parses_with_url_only() {
         let cli =
@@ -862,6 +1886,268 @@ mod tests {
         }
     }
 
+    #[test]
+    fn cache_stats_parses() {
+        let cli =
+            Cli::try_parse_from(["pginf", "cache", "stats", "--json"]).unwrap();
+        match cli.command {
+            Commands::Cache {
+                command: CacheCommand::Stats { json },
+            } => assert!(json),
+            _ => panic!("expected cache stats"),
+        }
+    }
+
+    #[test]
+    fn cache_migrate_requires_known_compression() {
+        let cli = Cli::try_parse_from([
+            "pginf",
+            "cache",
+            "migrate",
+            "--compression",
+            "zstd",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Cache {
+                command: CacheCommand::Migrate { compression },
+            } => assert_eq!(compression, "zstd"),
+            _ => panic!("expected cache migrate"),
+        }
+
+        let err = Cli::try_parse_from([
+            "pginf",
+            "cache",
+            "migrate",
+            "--compression",
+            "brotli",
+        ])
+        .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidValue);
+    }
+
+    #[test]
+    fn cache_compression_flag_feeds_cache_config() {
+        let cli = Cli::try_parse_from([
+            "pginf",
+            "--cache-compression",
+            "gzip",
+            "fetch",
+            "https://example.com",
+        ])
+        .unwrap();
+        let config = cache_config(&cli, false, true);
+        assert_eq!(config.compression, cache::Compression::Gzip);
+        assert!(config.enabled);
+        assert!(config.refresh);
+    }
+
+    #[test]
+    fn cache_backend_flag_feeds_cache_config() {
+        let cli = Cli::try_parse_from([
+            "pginf",
+            "--cache-backend",
+            "sqlite",
+            "cache",
+            "stats",
+        ])
+        .unwrap();
+        let config = cache_config(&cli, false, false);
+        assert_eq!(config.backend, cache::CacheBackend::Sqlite);
+        assert_eq!(config.failure_ttl_secs, cache::DEFAULT_FAILURE_TTL_SECS);
+
+        let err = Cli::try_parse_from([
+            "pginf",
+            "--cache-backend",
+            "redis",
+            "cache",
+            "stats",
+        ])
+        .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidValue);
+    }
+
+    #[test]
+    fn config_backend_applies_unless_flag_given() {
+        let mut cli = Cli::try_parse_from(["pginf", "cache", "stats"]).unwrap();
+        assert_eq!(cache_backend(&cli), cache::CacheBackend::File);
+        cli.config.backend = Some(cache::CacheBackend::Sqlite);
+        let config = cache_config(&cli, false, false);
+        assert_eq!(config.backend, cache::CacheBackend::Sqlite);
+        #[cfg(not(feature = "sqlite"))]
+        assert!(matches!(
+            cache::open(config),
+            Err(cache::CacheError::BackendUnavailable(_))
+        ));
+
+        let mut cli = Cli::try_parse_from([
+            "pginf",
+            "--cache-backend",
+            "file",
+            "cache",
+            "stats",
+        ])
+        .unwrap();
+        cli.config.backend = Some(cache::CacheBackend::Sqlite);
+        assert_eq!(cache_backend(&cli), cache::CacheBackend::File);
+    }
+
+    #[test]
+    fn failure_ttl_flag_feeds_cache_config() {
+        let cli = Cli::try_parse_from([
+            "pginf",
+            "links",
+            "https://example.com",
+            "--failure-ttl",
+            "0",
+        ])
+        .unwrap();
+        assert_eq!(cache_config(&cli, false, false).failure_ttl_secs, 0);
+    }
+
+    #[test]
+    fn normalize_flag_feeds_cache_keys_and_links() {
+        let cli = Cli::try_parse_from([
+            "pginf",
+            "--normalize",
+            "sort-query,strip-tracking",
+            "links",
+            "https://example.com",
+        ])
+        .unwrap();
+        let policy = normalize::NormalizePolicy::from_rules(&[
+            normalize::NormalizeRule::SortQuery,
+            normalize::NormalizeRule::StripTracking,
+        ]);
+        assert_eq!(cache_config(&cli, false, false).normalization, policy);
+        assert_eq!(link_options(&cli).url_policy, policy);
+
+        let cli = Cli::try_parse_from([
+            "pginf",
+            "links",
+            "https://example.com",
+            "--normalize",
+            "all",
+        ])
+        .unwrap();
+        assert_eq!(normalize_policy(&cli), normalize::NormalizePolicy::all());
+
+        let err = Cli::try_parse_from([
+            "pginf",
+            "--normalize",
+            "lowercase-path",
+            "links",
+            "https://example.com",
+        ])
+        .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidValue);
+    }
+
+    #[test]
+    fn diff_defaults_to_previous_against_newest() {
+        let cli =
+            Cli::try_parse_from(["pginf", "diff", "https://example.com"]).unwrap();
+        assert_eq!(
+            cache_config(&cli, false, false).history_limit,
+            cache::DEFAULT_HISTORY_LIMIT
+        );
+        match cli.command {
+            Commands::Diff {
+                url,
+                from,
+                to,
+                format,
+                refresh,
+            } => {
+                assert_eq!(url, "https://example.com");
+                assert_eq!(from, 1);
+                assert_eq!(to, 0);
+                assert_eq!(format, "text");
+                assert!(!refresh);
+            }
+            _ => panic!("expected diff command"),
+        }
+    }
+
+    #[test]
+    fn cache_list_parses_filters() {
+        let cli = Cli::try_parse_from([
+            "pginf",
+            "cache",
+            "list",
+            "--domain",
+            "example.com",
+            "--status",
+            "404",
+            "--limit",
+            "5",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Cache {
+                command:
+                    CacheCommand::List {
+                        domain,
+                        status,
+                        limit,
+                        json,
+                    },
+            } => {
+                assert_eq!(domain.as_deref(), Some("example.com"));
+                assert_eq!(status, Some(404));
+                assert_eq!(limit, Some(5));
+                assert!(!json);
+            }
+            _ => panic!("expected cache list command"),
+        }
+    }
+
+    #[test]
+    fn cache_export_and_import_parse_paths() {
+        let cli = Cli::try_parse_from([
+            "pginf",
+            "cache",
+            "export",
+            "--warc",
+            "out.warc.gz",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Cache {
+                command: CacheCommand::Export { warc },
+            } => assert_eq!(warc, std::path::PathBuf::from("out.warc.gz")),
+            _ => panic!("expected cache export"),
+        }
+
+        let cli =
+            Cli::try_parse_from(["pginf", "cache", "import", "crawl.warc.gz"])
+                .unwrap();
+        match cli.command {
+            Commands::Cache {
+                command: CacheCommand::Import { path },
+            } => assert_eq!(path, std::path::PathBuf::from("crawl.warc.gz")),
+            _ => panic!("expected cache import"),
+        }
+
+        let err = Cli::try_parse_from(["pginf", "cache", "export"]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn format_cache_stats_reports_saved_space() {
+        let mut stats = cache::CacheStats {
+            entries: 2,
+            body_bytes: 1000,
+            stored_bytes: 250,
+            ..Default::default()
+        };
+        stats.by_compression.insert("zstd".to_string(), 2);
+        let out =
+            format_cache_stats_markdown(&stats, &cache::CacheConfig::default());
+        assert!(out.contains("**Saved:** 750 bytes (75.0%)"));
+        assert!(out.contains("zstd: 2"));
+    }
+
     #[test]
     fn install_skills_local_parses() {
         let cli =
@@ -908,6 +2194,9 @@ mod tests {
                 ..Default::default()
             },
             from_cache: false,
+            fetched_at: None,
+            cache_read_ms: None,
+            cache_root: None,
         };
         let out = format_fetch_markdown(&resolved);
         assert!(out.contains("200"));
@@ -915,6 +2204,130 @@ mod tests {
         assert!(out.contains("example.com"));
     }
 
+    #[test]
+    fn format_fetch_markdown_separates_cache_read_from_fetch_timing() {
+        let resolved = resolve::ResolveOutput {
+            fetch_result: client::FetchResult {
+                input_url: "https://example.com".to_string(),
+                final_url: "https://example.com".to_string(),
+                status: 200,
+                duration_ms: 420,
+                attempts: 1,
+                ..Default::default()
+            },
+            from_cache: true,
+            fetched_at: Some("1775600000".to_string()),
+            cache_read_ms: Some(3),
+            cache_root: Some(std::path::PathBuf::from("/work/.pginf")),
+        };
+        let out = format_fetch_markdown(&resolved);
+        assert!(out.contains("420ms (original fetch)"));
+        assert!(out.contains("**Cache read:** 3ms"));
+        assert!(out.contains("1775600000"));
+        assert!(out.contains("**Cache root:** /work/.pginf"));
+
+        let parsed: serde_json::Value =
+            serde_json::from_str(&format_fetch_json(&resolved)).unwrap();
+        assert_eq!(parsed["duration_ms"], 420);
+        assert_eq!(parsed["cache_read_ms"], 3);
+    }
+
+    #[test]
+    fn offline_flag_feeds_cache_config_and_fetch_takes_many_urls() {
+        let cli = Cli::try_parse_from([
+            "pginf",
+            "--offline",
+            "fetch",
+            "https://example.com/a",
+            "https://example.com/b",
+        ])
+        .unwrap();
+        assert!(cache_config(&cli, false, false).offline);
+        match cli.command {
+            Commands::Fetch { urls, .. } => assert_eq!(urls.len(), 2),
+            _ => panic!("expected fetch command"),
+        }
+    }
+
+    #[test]
+    fn offline_flag_overrides_config_option() {
+        let mut cli =
+            Cli::try_parse_from(["pginf", "meta", "https://example.com"]).unwrap();
+        cli.config.offline = Some(true);
+        assert!(cache_config(&cli, false, false).offline);
+
+        let mut cli = Cli::try_parse_from([
+            "pginf",
+            "--offline=false",
+            "meta",
+            "https://example.com",
+        ])
+        .unwrap();
+        cli.config.offline = Some(true);
+        assert!(!cache_config(&cli, false, false).offline);
+    }
+
+    #[test]
+    fn cache_dir_flag_sets_cache_root() {
+        let cli = Cli::try_parse_from([
+            "pginf",
+            "--cache-dir",
+            "/tmp/shared-pginf",
+            "meta",
+            "https://example.com",
+        ])
+        .unwrap();
+        assert_eq!(
+            cache_config(&cli, false, false).root_dir,
+            std::path::PathBuf::from("/tmp/shared-pginf")
+        );
+    }
+
+    #[test]
+    fn fetch_batch_output_lists_cache_misses() {
+        let resolved = vec![resolve::ResolveOutput {
+            fetch_result: client::FetchResult {
+                input_url: "https://example.com/a".to_string(),
+                final_url: "https://example.com/a".to_string(),
+                status: 200,
+                ..Default::default()
+            },
+            from_cache: true,
+            fetched_at: None,
+            cache_read_ms: None,
+            cache_root: None,
+        }];
+        let misses = vec!["https://example.com/b".to_string()];
+        let failures = vec![(
+            "https://example.com/c".to_string(),
+            "cached failure".to_string(),
+        )];
+
+        let out = format_fetch_batch_markdown(&resolved, &misses, &failures);
+        assert!(out.contains("https://example.com/a"));
+        assert!(out.contains("## Cache Misses (1)\n\n- https://example.com/b"));
+        assert!(
+            out.contains(
+                "## Errors (1)\n\n- https://example.com/c: cached failure"
+            )
+        );
+
+        let parsed: serde_json::Value = serde_json::from_str(
+            &format_fetch_batch_json(&resolved, &misses, &failures),
+        )
+        .unwrap();
+        assert_eq!(parsed["results"][0]["status"], 200);
+        assert_eq!(parsed["cache_misses"][0], "https://example.com/b");
+        assert_eq!(parsed["errors"][0]["url"], "https://example.com/c");
+        assert_eq!(parsed["errors"][0]["error"], "cached failure");
+
+        assert_eq!(fetch_batch_error(3, 0, 0), None);
+        assert_eq!(
+            fetch_batch_error(3, 1, 1).as_deref(),
+            Some("2 of 3 URLs failed or not in cache (1 not in cache)")
+        );
+    }
+
     #[test]
     fn headings_parses_url() {
         let cli = Cli::try_parse_from(["pginf", "headings", "https://example.com"])
@@ -1040,6 +2453,9 @@ mod tests {
                 ..Default::default()
             },
             from_cache: false,
+            fetched_at: None,
+            cache_read_ms: None,
+            cache_root: None,
         };
         let out = format_fetch_json(&resolved);
         let parsed: serde_json::Value = serde_json::from_str(&out).unwrap();
@@ -0,0 +1,364 @@
+//! URL normalization shared by cache keys and link extraction.
+
+use url::Url;
+use url::form_urlencoded;
+
+/// Query parameters that only carry campaign or click attribution. `*`
+/// matches any run of characters.
+pub const TRACKING_PARAMS: &[&str] = &[
+    "utm_*", "fbclid", "gclid", "dclid", "msclkid", "yclid", "mc_cid", "mc_eid",
+    "_hsenc", "_hsmi", "igshid",
+];
+
+/// Tracking parameter patterns: the built-in [`TRACKING_PARAMS`] plus any
+/// extras from config. Matching ignores ASCII case.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrackingParams {
+    patterns: Vec<String>,
+}
+
+impl Default for TrackingParams {
+    fn default() -> Self {
+        Self {
+            patterns: TRACKING_PARAMS.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+}
+
+impl TrackingParams {
+    /// The built-in list extended with `extra` patterns.
+    pub fn with_extra<S: AsRef<str>>(extra: &[S]) -> Self {
+        let mut params = Self::default();
+        for pattern in extra {
+            let pattern = pattern.as_ref().trim().to_ascii_lowercase();
+            if !pattern.is_empty() && !params.patterns.contains(&pattern) {
+                params.patterns.push(pattern);
+            }
+        }
+        params
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        let name = name.to_ascii_lowercase();
+        self.patterns
+            .iter()
+            .any(|pattern| glob_match(pattern.as_bytes(), name.as_bytes()))
+    }
+}
+
+/// One opt-in normalization rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NormalizeRule {
+    /// `?b=2&a=1` -> `?a=1&b=2`
+    SortQuery,
+    /// Drop tracking parameters ([`TrackingParams`]).
+    StripTracking,
+    /// Drop `key=` pairs and empty `&&` segments.
+    RemoveEmptyParams,
+    /// `/docs/` -> `/docs`; the root path is kept.
+    TrailingSlash,
+    /// `/docs/index.html` -> `/docs/`
+    IndexHtml,
+    /// `%7Euser` -> `~user` for letters, digits, `-`, `.`, `_`, `~`.
+    DecodeUnreserved,
+}
+
+impl NormalizeRule {
+    pub const ALL: [NormalizeRule; 6] = [
+        NormalizeRule::SortQuery,
+        NormalizeRule::StripTracking,
+        NormalizeRule::RemoveEmptyParams,
+        NormalizeRule::TrailingSlash,
+        NormalizeRule::IndexHtml,
+        NormalizeRule::DecodeUnreserved,
+    ];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "sort-query" => Some(Self::SortQuery),
+            "strip-tracking" => Some(Self::StripTracking),
+            "remove-empty-params" => Some(Self::RemoveEmptyParams),
+            "trailing-slash" => Some(Self::TrailingSlash),
+            "index-html" => Some(Self::IndexHtml),
+            "decode-unreserved" => Some(Self::DecodeUnreserved),
+            _ => None,
+        }
+    }
+}
+
+/// Which normalization rules to apply. The default applies none, which keeps
+/// the baseline behavior (no fragment, lowercase host, no default port).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NormalizePolicy {
+    pub sort_query: bool,
+    pub strip_tracking: bool,
+    /// Parameters dropped by `strip_tracking`.
+    pub tracking_params: TrackingParams,
+    pub remove_empty_params: bool,
+    pub trailing_slash: bool,
+    pub index_html: bool,
+    pub decode_unreserved: bool,
+}
+
+impl NormalizePolicy {
+    pub fn all() -> Self {
+        Self::from_rules(&NormalizeRule::ALL)
+    }
+
+    pub fn from_rules(rules: &[NormalizeRule]) -> Self {
+        let mut policy = Self::default();
+        for rule in rules {
+            match rule {
+                NormalizeRule::SortQuery => policy.sort_query = true,
+                NormalizeRule::StripTracking => policy.strip_tracking = true,
+                NormalizeRule::RemoveEmptyParams => {
+                    policy.remove_empty_params = true
+                }
+                NormalizeRule::TrailingSlash => policy.trailing_slash = true,
+                NormalizeRule::IndexHtml => policy.index_html = true,
+                NormalizeRule::DecodeUnreserved => policy.decode_unreserved = true,
+            }
+        }
+        policy
+    }
+
+    /// Applies the baseline cleanup and every enabled rule to `url`.
+    pub fn apply(&self, url: &mut Url) {
+        url.set_fragment(None);
+        if let Some(host) = url.host_str().map(|h| h.to_ascii_lowercase()) {
+            let _ = url.set_host(Some(&host));
+        }
+        if matches!(
+            (url.scheme(), url.port()),
+            ("http", Some(80)) | ("https", Some(443))
+        ) {
+            let _ = url.set_port(None);
+        }
+
+        if !url.cannot_be_a_base() {
+            let mut path = url.path().to_string();
+            if self.decode_unreserved {
+                path = decode_unreserved(&path);
+            }
+            if self.index_html
+                && let Some(stripped) = strip_index_file(&path)
+            {
+                path = stripped.to_string();
+            }
+            if self.trailing_slash && path.len() > 1 {
+                path = path.trim_end_matches('/').to_string();
+                if path.is_empty() {
+                    path.push('/');
+                }
+            }
+            if path != url.path() {
+                url.set_path(&path);
+            }
+        }
+
+        self.apply_query(url);
+    }
+
+    /// Removes tracking parameters from the query and touches nothing else.
+    pub fn strip_tracking_params(&self, url: &mut Url) {
+        NormalizePolicy {
+            strip_tracking: true,
+            tracking_params: self.tracking_params.clone(),
+            ..Default::default()
+        }
+        .apply_query(url);
+    }
+
+    fn apply_query(&self, url: &mut Url) {
+        if let Some(query) = url.query().map(String::from) {
+            let query = self.normalize_query(&query);
+            url.set_query((!query.is_empty()).then_some(query.as_str()));
+        }
+    }
+
+    fn normalize_query(&self, query: &str) -> String {
+        let query = if self.decode_unreserved {
+            decode_unreserved(query)
+        } else {
+            query.to_string()
+        };
+        if !(self.strip_tracking || self.remove_empty_params || self.sort_query) {
+            return query;
+        }
+
+        let mut pairs: Vec<(String, &str)> = query
+            .split('&')
+            .filter(|segment| !segment.is_empty())
+            .map(|segment| {
+                let key = form_urlencoded::parse(segment.as_bytes())
+                    .next()
+                    .map(|(key, _)| key.into_owned())
+                    .unwrap_or_default();
+                (key, segment)
+            })
+            .filter(|(key, segment)| {
+                !(self.strip_tracking && self.tracking_params.matches(key)
+                    || self.remove_empty_params && segment.ends_with('='))
+            })
+            .collect();
+        if self.sort_query {
+            pairs.sort();
+        }
+        pairs
+            .into_iter()
+            .map(|(_, segment)| segment)
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+}
+
+/// `*` matches any run of bytes; everything else matches literally.
+fn glob_match(pattern: &[u8], name: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some((b'*', rest)) => {
+            (0..=name.len()).any(|skip| glob_match(rest, &name[skip..]))
+        }
+        Some((first, rest)) => {
+            name.first() == Some(first) && glob_match(rest, &name[1..])
+        }
+    }
+}
+
+fn strip_index_file(path: &str) -> Option<&str> {
+    let (dir, file) = path.rsplit_once('/')?;
+    (file.eq_ignore_ascii_case("index.html")
+        || file.eq_ignore_ascii_case("index.htm"))
+    .then(|| &path[..=dir.len()])
+}
+
+/// Decodes `%XX` escapes of RFC 3986 unreserved characters, leaving every
+/// other escape untouched.
+fn decode_unreserved(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut out = String::with_capacity(input.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(hex) = input.get(i + 1..i + 3)
+            && let Ok(byte) = u8::from_str_radix(hex, 16)
+            && (byte.is_ascii_alphanumeric() || b"-._~".contains(&byte))
+        {
+            out.push(byte as char);
+            i += 3;
+            continue;
+        }
+        let ch = input[i..].chars().next().unwrap_or_default();
+        out.push(ch);
+        i += ch.len_utf8();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalized(policy: &NormalizePolicy, url: &str) -> String {
+        let mut url = Url::parse(url).unwrap();
+        policy.apply(&mut url);
+        url.to_string()
+    }
+
+    #[test]
+    fn default_policy_only_does_baseline_cleanup() {
+        let policy = NormalizePolicy::default();
+        assert_eq!(
+            normalized(&policy, "https://Example.com:443/a/?b=2&a=#top"),
+            "https://example.com/a/?b=2&a="
+        );
+    }
+
+    #[test]
+    fn query_rules_strip_drop_and_sort() {
+        let policy = NormalizePolicy::from_rules(&[
+            NormalizeRule::SortQuery,
+            NormalizeRule::StripTracking,
+            NormalizeRule::RemoveEmptyParams,
+        ]);
+        assert_eq!(
+            normalized(
+                &policy,
+                "https://example.com/p?z=1&UTM_source=x&empty=&&a=%20b&gclid=1"
+            ),
+            "https://example.com/p?a=%20b&z=1"
+        );
+        assert_eq!(
+            normalized(&policy, "https://example.com/p?utm_medium=x"),
+            "https://example.com/p"
+        );
+    }
+
+    #[test]
+    fn tracking_params_match_globs_and_config_extras() {
+        let defaults = TrackingParams::default();
+        assert!(defaults.matches("UTM_Campaign"));
+        assert!(defaults.matches("mc_cid"));
+        assert!(!defaults.matches("ref"));
+        assert!(!defaults.matches("utm"));
+
+        let params = TrackingParams::with_extra(&["ref", "pk_*", "*_trk"]);
+        assert!(params.matches("ref"));
+        assert!(params.matches("pk_campaign"));
+        assert!(params.matches("li_trk"));
+        assert!(!params.matches("reference"));
+
+        let policy = NormalizePolicy {
+            tracking_params: params,
+            ..Default::default()
+        };
+        let mut url =
+            Url::parse("https://example.com/p?ref=hn&id=7&pk_kwd=x&utm_id=1")
+                .unwrap();
+        policy.strip_tracking_params(&mut url);
+        assert_eq!(url.as_str(), "https://example.com/p?id=7");
+    }
+
+    #[test]
+    fn path_rules_collapse_slashes_and_index_files() {
+        let policy = NormalizePolicy::from_rules(&[
+            NormalizeRule::TrailingSlash,
+            NormalizeRule::IndexHtml,
+        ]);
+        assert_eq!(
+            normalized(&policy, "https://example.com/docs/index.html"),
+            "https://example.com/docs"
+        );
+        assert_eq!(
+            normalized(&policy, "https://example.com/docs//"),
+            "https://example.com/docs"
+        );
+        assert_eq!(
+            normalized(&policy, "https://example.com/index.htm"),
+            "https://example.com/"
+        );
+        assert_eq!(
+            normalized(&policy, "https://example.com/"),
+            "https://example.com/"
+        );
+    }
+
+    #[test]
+    fn decodes_only_unreserved_escapes() {
+        let policy =
+            NormalizePolicy::from_rules(&[NormalizeRule::DecodeUnreserved]);
+        assert_eq!(
+            normalized(&policy, "https://example.com/%7Euser/a%2Fb%41?q=%2D%20"),
+            "https://example.com/~user/a%2FbA?q=-%20"
+        );
+    }
+
+    #[test]
+    fn leaves_non_hierarchical_urls_alone() {
+        let policy = NormalizePolicy::all();
+        assert_eq!(
+            normalized(&policy, "mailto:foo@bar.com"),
+            "mailto:foo@bar.com"
+        );
+    }
+}
@@ -1,22 +1,27 @@
-use crate::cache::{Cache, FileCache};
+use std::path::PathBuf;
+
+use crate::cache::{self, CacheConfig};
 use crate::client::{ClientError, FetchResult, PageClient};
 
 pub struct ResolveOutput {
     pub fetch_result: FetchResult,
     pub from_cache: bool,
+    pub fetched_at: Option<String>,
+    pub cache_read_ms: Option<u64>,
+    /// Cache root consulted for this page, `None` with `--no-cache`.
+    pub cache_root: Option<PathBuf>,
 }
 
 pub async fn resolve_page(
     url: &str,
     client: &PageClient,
-    no_cache: bool,
-    refresh: bool,
+    cache_config: &CacheConfig,
 ) -> Result<ResolveOutput, ClientError> {
-    let cache = FileCache::new(crate::cache::CacheConfig {
-        enabled: !no_cache,
-        refresh,
-        ..Default::default()
-    });
+    let cache =
+        cache::open(cache_config.clone()).map_err(|e| ClientError::Request {
+            url: url.to_string(),
+            reason: e.to_string(),
+        })?;
     cache.init().map_err(|e| ClientError::Request {
         url: url.to_string(),
         reason: e.to_string(),
@@ -30,30 +35,59 @@ pub async fn resolve_page(
                 reason: e.to_string(),
             })?;
 
-    if !no_cache && !cache.should_refresh() {
+    // Offline, a cached copy is the only option, so `refresh` is ignored.
+    if cache_config.enabled && (!cache_config.refresh || cache_config.offline) {
+        let start = std::time::Instant::now();
         if let Some(cached) =
             cache.load(&cache_key).map_err(|e| ClientError::Request {
                 url: url.to_string(),
                 reason: e.to_string(),
             })?
         {
+            let cache_read_ms = start.elapsed().as_millis() as u64;
+            let fetched_at = cached.fetch.fetched_at.clone();
             return Ok(ResolveOutput {
-                fetch_result: FetchResult {
-                    input_url: cached.fetch.input_url,
-                    final_url: cached.fetch.final_url,
-                    status: cached.fetch.status,
-                    headers: cached.headers,
-                    body: cached.html,
-                    ..Default::default()
-                },
+                fetch_result: FetchResult::from_cached_page(cached),
                 from_cache: true,
+                fetched_at: Some(fetched_at),
+                cache_read_ms: Some(cache_read_ms),
+                cache_root: Some(cache_config.root_dir.clone()),
             });
         }
+
+        if let Some(failure) =
+            cache
+                .load_failure(&cache_key)
+                .map_err(|e| ClientError::Request {
+                    url: url.to_string(),
+                    reason: e.to_string(),
+                })?
+        {
+            return Err(ClientError::from_cached_failure(failure));
+        }
+    }
+
+    if cache_config.offline {
+        return Err(ClientError::NotCached {
+            url: url.to_string(),
+        });
     }
 
-    let fetch_result = client.fetch(url).await?;
+    let fetch_result = match client.fetch_counting_attempts(url).await {
+        (Ok(fetch_result), _) => fetch_result,
+        (Err(err), attempts) => {
+            if cache_config.enabled
+                && let Some(failure) = err.to_cached_failure(attempts)
+            {
+                // Remembering the failure is best effort; report the fetch
+                // error either way.
+                let _ = cache.store_failure(&cache_key, &failure);
+            }
+            return Err(err);
+        }
+    };
 
-    if !no_cache {
+    if cache_config.enabled {
         cache.store(fetch_result.to_cached_page()).map_err(|e| {
             ClientError::Request {
                 url: url.to_string(),
@@ -65,5 +99,103 @@ pub async fn resolve_page(
     Ok(ResolveOutput {
         fetch_result,
         from_cache: false,
+        fetched_at: None,
+        cache_read_ms: None,
+        cache_root: cache_config.enabled.then(|| cache_config.root_dir.clone()),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::*;
+    use crate::cache::Cache;
+
+    fn offline_config() -> CacheConfig {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        CacheConfig {
+            root_dir: std::env::temp_dir()
+                .join(format!("pageinfo-resolve-test-{nanos}")),
+            offline: true,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn offline_miss_returns_not_cached_without_fetching() {
+        let config = offline_config();
+        // Port 9 (discard) would fail loudly if a fetch were attempted.
+        let url = "http://127.0.0.1:9/missing";
+
+        let err = resolve_page(url, &PageClient::builder().build(), &config)
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(err, ClientError::NotCached { .. }));
+        assert!(err.to_string().contains("not in cache"));
+
+        let _ = std::fs::remove_dir_all(config.root_dir);
+    }
+
+    #[tokio::test]
+    async fn offline_hit_is_served_even_with_refresh() {
+        let config = CacheConfig {
+            refresh: true,
+            ..offline_config()
+        };
+        let url = "http://127.0.0.1:9/cached";
+        let cache = crate::cache::FileCache::new(config.clone());
+        cache.init().unwrap();
+        cache
+            .store(
+                FetchResult {
+                    input_url: url.to_string(),
+                    final_url: url.to_string(),
+                    status: 200,
+                    body: "<html></html>".to_string(),
+                    ..Default::default()
+                }
+                .to_cached_page(),
+            )
+            .unwrap();
+
+        let resolved = resolve_page(url, &PageClient::builder().build(), &config)
+            .await
+            .unwrap();
+        assert!(resolved.from_cache);
+        assert_eq!(resolved.fetch_result.body, "<html></html>");
+
+        std::fs::remove_dir_all(config.root_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn failed_fetch_is_served_from_cache_until_refresh() {
+        let config = CacheConfig {
+            offline: false,
+            ..offline_config()
+        };
+        // Nothing listens on port 9, so every real fetch fails.
+        let url = "http://127.0.0.1:9/broken";
+        let client = PageClient::builder().build();
+
+        let first = resolve_page(url, &client, &config).await.err().unwrap();
+        assert!(!matches!(first, ClientError::CachedFailure { .. }));
+
+        let second = resolve_page(url, &client, &config).await.err().unwrap();
+        assert!(matches!(second, ClientError::CachedFailure { .. }));
+        assert!(second.to_string().contains("cached failure"));
+
+        let refreshed = CacheConfig {
+            refresh: true,
+            ..config.clone()
+        };
+        let third = resolve_page(url, &client, &refreshed).await.err().unwrap();
+        assert!(!matches!(third, ClientError::CachedFailure { .. }));
+
+        std::fs::remove_dir_all(config.root_dir).unwrap();
+    }
+}